@@ -1,5 +1,6 @@
 use crate::{
-    blocks::BLOCK_COLOR_AIR, camera::Camera, item::ItemName, render::Rect,
+    blocks::BLOCK_COLOR_AIR, camera::Camera, damage::Damage,
+    effect::StatusEffect, loot::LootTable, render::Rect,
 };
 use derive_builder::Builder;
 use glam::{IVec2, Vec2};
@@ -12,6 +13,9 @@ pub enum BlockName {
     Air,
     Dirt,
     Stone,
+    Lava,
+    Sludge,
+    Stash, // opens the player's stash
 }
 
 #[derive(Clone, Debug, PartialEq, Copy, Default)]
@@ -21,6 +25,7 @@ pub enum BlockFlag {
     Dig,
     Chop,
     Highlight,
+    Liquid,
 }
 
 #[derive(Clone, Debug, Copy, Builder)]
@@ -32,8 +37,12 @@ pub struct Block {
     pub required_level: u32,
     pub health: f32,
     pub max_health: f32,
-    pub drop_item: Option<ItemName>,
+    pub loot: Option<&'static LootTable>,
+    pub xp: u32, // given for breaking it
     pub is_solid: bool,
+    pub touch_damage: Option<Damage>, // dealt to anything inside this block
+    pub fall_damage: f32, // scales fall damage for landing on (or in) it
+    pub touch_effect: Option<StatusEffect>, // kept on anything inside it
     flags: [Option<BlockFlag>; 6],
     flag_count: usize,
     pub last_hit_tick: u64,
@@ -88,18 +97,18 @@ impl Block {
             }
         })
     }
+    #[must_use]
+    pub fn has_flag(&self, flag: BlockFlag) -> bool {
+        self.flags.contains(&Some(flag))
+    }
     pub fn add_flag(&mut self, flag: BlockFlag) {
         // if we are full on flags, don't try to add another
         if self.flag_count >= self.flags.len() {
             return;
         }
         // if the flag already exists, don't add it again
-        for i in 0..self.flag_count {
-            if let Some(f) = self.flags[i] {
-                if f == flag {
-                    return;
-                }
-            }
+        if self.has_flag(flag) {
+            return;
         }
         // add the flag
         self.flags[self.flag_count] = Some(flag);
@@ -107,11 +116,9 @@ impl Block {
     }
     pub fn remove_flag(&mut self, flag: BlockFlag) {
         for i in 0..self.flag_count {
-            if let Some(f) = self.flags[i] {
-                if f == flag {
-                    self.remove_flag_by_index(i);
-                    break;
-                }
+            if self.flags[i] == Some(flag) {
+                self.remove_flag_by_index(i);
+                break;
             }
         }
     }
@@ -3,7 +3,9 @@ use glam::IVec2;
 use crate::{
     block::{Block, BlockName},
     block::{BlockBuilder, BlockFlag},
-    item::ItemName,
+    damage::{Damage, DamageSource},
+    effects::effect_poison,
+    loot_tables::{LOOT_DIRT, LOOT_STASH, LOOT_STONE},
 };
 
 pub const BLOCK_COLOR_AIR: (u8, u8, u8) = (135, 206, 235);
@@ -20,8 +22,12 @@ pub fn block_dirt(pos: IVec2) -> Block {
         .required_level(0)
         .max_health(50.0)
         .health(50.0)
-        .drop_item(Some(ItemName::Dirt))
+        .loot(Some(&LOOT_DIRT))
+        .xp(1)
         .is_solid(true)
+        .touch_damage(None)
+        .fall_damage(0.75)
+        .touch_effect(None)
         .last_hit_tick(0)
         .build()
         .unwrap()
@@ -37,10 +43,14 @@ pub fn block_air(pos: IVec2) -> Block {
         .required_level(0)
         .max_health(0.0)
         .health(0.0)
-        .drop_item(None)
+        .loot(None)
+        .xp(0)
         .is_solid(false)
+        .touch_damage(None)
         .flags([None, None, None, None, None, None])
         .flag_count(0)
+        .fall_damage(1.0)
+        .touch_effect(None)
         .last_hit_tick(0)
         .build()
         .unwrap()
@@ -58,9 +68,95 @@ pub fn block_stone(pos: IVec2) -> Block {
         .required_level(1)
         .max_health(100.0)
         .health(100.0)
-        .drop_item(Some(ItemName::Stone))
+        .loot(Some(&LOOT_STONE))
+        .xp(3)
         .is_solid(true)
+        .touch_damage(None)
+        .fall_damage(1.0)
+        .touch_effect(None)
         .last_hit_tick(0)
         .build()
         .unwrap()
 }
+
+#[must_use]
+pub fn block_lava(pos: IVec2) -> Block {
+    BlockBuilder::default()
+        .pos(pos)
+        .color((207, 16, 32))
+        .block_type(BlockName::Lava)
+        .can_collide(false)
+        .flags([Some(BlockFlag::Liquid), None, None, None, None, None])
+        .flag_count(1)
+        .required_level(0)
+        .max_health(0.0)
+        .health(0.0)
+        .loot(None)
+        .xp(0)
+        .is_solid(false)
+        .touch_damage(Some(Damage::new(20.0, DamageSource::Lava)))
+        .fall_damage(0.5)
+        .touch_effect(None)
+        .last_hit_tick(0)
+        .build()
+        .unwrap()
+}
+
+#[must_use]
+pub fn block_sludge(pos: IVec2) -> Block {
+    BlockBuilder::default()
+        .pos(pos)
+        .color((85, 107, 47))
+        .block_type(BlockName::Sludge)
+        .can_collide(false)
+        .flags([Some(BlockFlag::Liquid), None, None, None, None, None])
+        .flag_count(1)
+        .required_level(0)
+        .max_health(0.0)
+        .health(0.0)
+        .loot(None)
+        .xp(0)
+        .is_solid(false)
+        .touch_damage(None)
+        .fall_damage(0.25)
+        .touch_effect(Some(effect_poison(60 * 3, 1)))
+        .last_hit_tick(0)
+        .build()
+        .unwrap()
+}
+
+/// Where the player reaches their stash from, see `stash.rs`
+#[must_use]
+pub fn block_stash(pos: IVec2) -> Block {
+    BlockBuilder::default()
+        .pos(pos)
+        .color((120, 72, 160))
+        .block_type(BlockName::Stash)
+        .can_collide(true)
+        .flags([Some(BlockFlag::Chop), None, None, None, None, None])
+        .flag_count(1)
+        .required_level(0)
+        .max_health(80.0)
+        .health(80.0)
+        .loot(Some(&LOOT_STASH))
+        .xp(0)
+        .is_solid(true)
+        .touch_damage(None)
+        .fall_damage(1.0)
+        .touch_effect(None)
+        .last_hit_tick(0)
+        .build()
+        .unwrap()
+}
+
+#[must_use]
+pub fn block_from_name(name: BlockName, pos: IVec2) -> Block {
+    match name {
+        BlockName::Air => block_air(pos),
+        BlockName::Dirt => block_dirt(pos),
+        BlockName::Stone => block_stone(pos),
+        BlockName::Lava => block_lava(pos),
+        BlockName::Sludge => block_sludge(pos),
+        BlockName::Stash => block_stash(pos),
+    }
+}
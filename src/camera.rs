@@ -57,8 +57,7 @@ impl Camera {
     }
 
     pub fn scale_global_to_screen(&self, world_pos: Vec2) -> Vec2 {
-        let new = rotate(world_pos * self.pixels_per_unit(), -self.rot);
-        new
+        rotate(world_pos * self.pixels_per_unit(), -self.rot)
     }
 
     pub fn pixels_per_unit(&self) -> Vec2 {
@@ -0,0 +1,124 @@
+use crate::{
+    components::{Collider, Health, Position},
+    damage::{Damage, DamageSource, Dead, deal_damage},
+    item::WeaponProps,
+    physics::boxes_overlap,
+    utils::Direction,
+};
+use glam::Vec2;
+use hecs::{Entity, Without, World as HecsWorld};
+
+/// The box (top left, size) a swing covers, `reach` long, in front of
+/// a box at `pos` of `size` looking towards `look_dir`
+#[must_use]
+pub fn melee_hitbox(
+    pos: Vec2,
+    size: Vec2,
+    look_dir: Direction,
+    reach: f32,
+) -> (Vec2, Vec2) {
+    let center = pos + size / 2.0;
+    let width = size.max_element().max(1.0); // across the swing
+    match look_dir {
+        Direction::Left => (
+            Vec2::new(pos.x - reach, center.y - width / 2.0),
+            Vec2::new(reach, width),
+        ),
+        Direction::Up => (
+            Vec2::new(center.x - width / 2.0, pos.y - reach),
+            Vec2::new(width, reach),
+        ),
+        Direction::Down => (
+            Vec2::new(center.x - width / 2.0, pos.y + size.y),
+            Vec2::new(width, reach),
+        ),
+        // not looking anywhere yet, swing to the right
+        Direction::Right | Direction::None => (
+            Vec2::new(pos.x + size.x, center.y - width / 2.0),
+            Vec2::new(reach, width),
+        ),
+    }
+}
+
+/// Swing `weapon` from `attacker` towards `look_dir`, hurting and knocking
+/// back everything with health the swing touches. Returns how many were hit
+pub fn melee_attack(
+    ecs: &mut HecsWorld,
+    attacker: Entity,
+    weapon: &WeaponProps,
+    look_dir: Direction,
+) -> usize {
+    let Ok((pos, collider)) =
+        ecs.query_one_mut::<(&Position, &Collider)>(attacker)
+    else {
+        return 0;
+    };
+    let (hit_pos, hit_size) =
+        melee_hitbox(pos.0, collider.size, look_dir, weapon.reach);
+
+    let dir = match look_dir.as_ivec2().as_vec2() {
+        Vec2::ZERO => Vec2::X,
+        dir => dir,
+    };
+    // sideways hits pop things up a little too
+    let knockback = if dir.y == 0.0 {
+        Vec2::new(dir.x, -0.5).normalize()
+    } else {
+        dir
+    } * weapon.knockback;
+    let damage = Damage::new(weapon.damage, DamageSource::Melee)
+        .with_knockback(knockback)
+        .with_effect(weapon.on_hit)
+        .with_attacker(attacker);
+
+    let hits: Vec<Entity> = ecs
+        .query_mut::<Without<(&Position, &Collider), &Dead>>()
+        .with::<&Health>()
+        .into_iter()
+        .filter(|(entity, (pos, collider))| {
+            *entity != attacker
+                && boxes_overlap(hit_pos, hit_size, pos.0, collider.size)
+        })
+        .map(|(entity, _)| entity)
+        .collect();
+    for &entity in &hits {
+        deal_damage(ecs, entity, damage);
+    }
+    hits.len()
+}
+
+/// Hurt everything with health within `radius` of the bottom middle of
+/// `attacker`, throwing it away and up. Returns how many were hit
+pub fn ground_slam(
+    ecs: &mut HecsWorld,
+    attacker: Entity,
+    damage: f32,
+    radius: f32,
+    knockback: f32,
+) -> usize {
+    let Ok((pos, collider)) =
+        ecs.query_one_mut::<(&Position, &Collider)>(attacker)
+    else {
+        return 0;
+    };
+    let origin = pos.0 + Vec2::new(collider.size.x / 2.0, collider.size.y);
+
+    let hits: Vec<(Entity, Vec2)> = ecs
+        .query_mut::<Without<(&Position, &Collider), &Dead>>()
+        .with::<&Health>()
+        .into_iter()
+        .filter(|(entity, _)| *entity != attacker)
+        .filter_map(|(entity, (pos, collider))| {
+            let offset = pos.center(collider.size) - origin;
+            (offset.length() <= radius).then_some((entity, offset))
+        })
+        .collect();
+    for &(entity, offset) in &hits {
+        let away = Vec2::new(offset.x.signum(), -1.0).normalize();
+        let hit = Damage::new(damage, DamageSource::Melee)
+            .with_knockback(away * knockback)
+            .with_attacker(attacker);
+        deal_damage(ecs, entity, hit);
+    }
+    hits.len()
+}
@@ -0,0 +1,120 @@
+use crate::physics::Contacts;
+use glam::Vec2;
+
+/// Top left corner of the entity, in world space
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Position(pub Vec2);
+
+/// Where the entity was at the start of the tick, for render interpolation
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PreviousPosition(pub Vec2);
+
+/// Units per second
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Velocity(pub Vec2);
+
+/// An axis aligned box that collides with solid blocks
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Collider {
+    pub size: Vec2,
+    pub contacts: Contacts, // updated by the movement system
+    pub impact: Vec2,       // velocity lost to collisions in the last move
+}
+
+/// Makes the entity fall
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Gravity {
+    pub terminal_velocity: f32,
+    pub scale: f32, // multiplies `GRAVITY_FORCE`
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+    pub invulnerability: u32, // ticks of immunity after each hit
+    pub invulnerable_ticks: u32, // immunity left from the last hit
+}
+
+/// Drawn as a filled rect the size of its `Collider`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Sprite {
+    pub color: (u8, u8, u8),
+}
+
+impl Position {
+    #[must_use]
+    pub fn center(&self, size: Vec2) -> Vec2 {
+        self.0 + size / 2.0
+    }
+
+    pub fn wrap_board(&mut self, board_x: u32) {
+        let width = board_x as f32;
+        if self.0.x < 0.0 {
+            self.0.x += width;
+        } else if self.0.x > width {
+            self.0.x -= width;
+        }
+    }
+}
+
+impl Collider {
+    #[must_use]
+    pub fn new(size: Vec2) -> Self {
+        Self {
+            size,
+            contacts: Contacts::empty(),
+            impact: Vec2::ZERO,
+        }
+    }
+
+    #[must_use]
+    pub fn on_ground(&self) -> bool {
+        self.contacts.contains(Contacts::BOTTOM)
+    }
+}
+
+impl Default for Gravity {
+    fn default() -> Self {
+        Self {
+            terminal_velocity: 53.0,
+            scale: 1.0,
+        }
+    }
+}
+
+impl Health {
+    #[must_use]
+    pub fn new(max: f32) -> Self {
+        Self {
+            current: max,
+            max,
+            invulnerability: 0,
+            invulnerable_ticks: 0,
+        }
+    }
+
+    #[must_use]
+    pub fn with_invulnerability(mut self, ticks: u32) -> Self {
+        self.invulnerability = ticks;
+        self
+    }
+
+    pub fn damage(&mut self, amount: f32) {
+        self.current = (self.current - amount).max(0.0);
+    }
+
+    pub fn heal(&mut self, amount: f32) {
+        self.current = (self.current + amount).min(self.max);
+    }
+
+    #[must_use]
+    pub fn is_invulnerable(&self) -> bool {
+        self.invulnerable_ticks > 0
+    }
+
+    #[must_use]
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0.0
+    }
+}
@@ -0,0 +1,352 @@
+use crate::{
+    block::BlockFlag,
+    components::{Collider, Health, Position, PreviousPosition, Velocity},
+    dropped_item::spawn_dropped_item,
+    effect::{StatusEffect, StatusEffects},
+    inventory::Inventory,
+    item::{Item, ItemType},
+    physics::overlapping_tiles,
+    player::Player,
+    stats::{StatName, Stats},
+    world::World,
+};
+use glam::Vec2;
+use hecs::{Entity, Without, World as HecsWorld};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DamageSource {
+    Fall,
+    Mob,
+    Lava,
+    Suffocation,
+    Melee,
+    Projectile,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Damage {
+    pub amount: f32,
+    pub source: DamageSource,
+    pub knockback: Vec2, // added to the velocity if the hit lands
+    pub effect: Option<StatusEffect>, // applied if the hit lands
+    pub attacker: Option<Entity>, // who dealt it, if anyone
+}
+
+impl Damage {
+    #[must_use]
+    pub const fn new(amount: f32, source: DamageSource) -> Self {
+        Self {
+            amount,
+            source,
+            knockback: Vec2::ZERO,
+            effect: None,
+            attacker: None,
+        }
+    }
+
+    #[must_use]
+    pub const fn with_knockback(mut self, knockback: Vec2) -> Self {
+        self.knockback = knockback;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_effect(mut self, effect: Option<StatusEffect>) -> Self {
+        self.effect = effect;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_attacker(mut self, attacker: Entity) -> Self {
+        self.attacker = Some(attacker);
+        self
+    }
+}
+
+impl DamageSource {
+    /// Can defense soften this, or does it always hit in full
+    #[must_use]
+    pub fn is_blockable(&self) -> bool {
+        !matches!(self, DamageSource::Fall | DamageSource::Suffocation)
+    }
+}
+
+/// Damage waiting for the damage system to apply it
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PendingDamage(pub Vec<Damage>);
+
+/// The entity whose hit landed on this one last, for handing out XP
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LastAttacker(pub Entity);
+
+/// The entity has died and is waiting to respawn
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Dead {
+    pub respawn_ticks: u32,
+}
+
+/// Where the entity comes back after dying
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpawnPoint(pub Vec2);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ItemLossRule {
+    /// nothing is lost
+    #[default]
+    Keep,
+    /// everything is dropped where you died
+    DropAll,
+    /// materials are dropped, tools (and such) are kept
+    DropMaterials,
+}
+
+/// Takes damage from landing faster than `safe_speed`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FallDamage {
+    pub safe_speed: f32,       // units per second
+    pub damage_per_speed: f32, // for every unit per second over `safe_speed`
+    pub multiplier: f32, // this entity's own, scaled by the fall damage stat
+}
+
+impl Default for FallDamage {
+    fn default() -> Self {
+        Self {
+            safe_speed: 25.0,
+            damage_per_speed: 4.0,
+            multiplier: 1.0,
+        }
+    }
+}
+
+/// How dying works for an entity that respawns
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DeathConfig {
+    pub respawn_ticks: u32,
+    pub item_loss: ItemLossRule,
+}
+
+impl Default for DeathConfig {
+    fn default() -> Self {
+        Self {
+            respawn_ticks: 60 * 3,
+            item_loss: ItemLossRule::DropMaterials,
+        }
+    }
+}
+
+impl ItemLossRule {
+    #[must_use]
+    pub fn drops(&self, item: &Item) -> bool {
+        match self {
+            ItemLossRule::Keep => false,
+            ItemLossRule::DropAll => true,
+            ItemLossRule::DropMaterials => item.item_type == ItemType::Material,
+        }
+    }
+}
+
+/// Queue `damage` to be dealt to `entity` this tick
+pub fn deal_damage(ecs: &mut HecsWorld, entity: Entity, damage: Damage) {
+    if let Ok(mut pending) = ecs.get::<&mut PendingDamage>(entity) {
+        pending.0.push(damage);
+        return;
+    }
+    // no queue yet (or no entity, which insert will ignore)
+    let _ = ecs.insert_one(entity, PendingDamage(vec![damage]));
+}
+
+/// Hurt (or affect) anything standing in a dangerous block,
+/// or stuck inside a solid one
+pub fn hazard_system(ecs: &mut HecsWorld, map: &World) {
+    let mut hits = Vec::new();
+    for (entity, (pos, collider, mut effects)) in ecs
+        .query_mut::<Without<
+            (&Position, &Collider, Option<&mut StatusEffects>),
+            &Dead,
+        >>()
+        .with::<&Health>()
+    {
+        let mut suffocating = false;
+        for block in map.blocks_overlapping(pos.0, collider.size) {
+            if let Some(damage) = block.touch_damage {
+                hits.push((entity, damage));
+            }
+            if let (Some(effects), Some(effect)) =
+                (effects.as_deref_mut(), block.touch_effect)
+            {
+                effects.keep_applied(effect);
+            }
+            suffocating |= block.is_solid;
+        }
+        if suffocating {
+            hits.push((entity, Damage::new(5.0, DamageSource::Suffocation)));
+        }
+    }
+    for (entity, damage) in hits {
+        deal_damage(ecs, entity, damage);
+    }
+}
+
+/// How much the blocks under a landing box (and any liquid it is in)
+/// soften the fall. The softest one wins
+fn landing_multiplier(map: &World, pos: Vec2, size: Vec2) -> f32 {
+    let below = (pos.y + size.y).round() as i32;
+    let landed_on = overlapping_tiles(pos.x, pos.x + size.x)
+        .filter_map(|x| map.get_block(x, below))
+        .filter(|block| block.can_collide);
+    let liquids = map
+        .blocks_overlapping(pos, size)
+        .filter(|block| block.has_flag(BlockFlag::Liquid));
+    landed_on
+        .chain(liquids)
+        .map(|block| block.fall_damage)
+        .fold(1.0, f32::min)
+}
+
+/// Turn hard landings into fall damage
+pub fn fall_damage_system(ecs: &mut HecsWorld, map: &World) {
+    let mut hits = Vec::new();
+    for (entity, (pos, collider, fall, stats)) in ecs.query_mut::<Without<
+        (&Position, &Collider, &FallDamage, Option<&Stats>),
+        &Dead,
+    >>() {
+        let speed = collider.impact.y; // +y is down
+        if !collider.on_ground() || speed <= fall.safe_speed {
+            continue;
+        }
+        // equipment and effects, never below immune
+        let stat = stats.map_or(1.0, |stats| stats.get(StatName::FallDamage));
+        let amount = (speed - fall.safe_speed)
+            * fall.damage_per_speed
+            * fall.multiplier
+            * stat.max(0.0)
+            * landing_multiplier(map, pos.0, collider.size);
+        if amount > 0.0 {
+            hits.push((entity, Damage::new(amount, DamageSource::Fall)));
+        }
+    }
+    for (entity, damage) in hits {
+        deal_damage(ecs, entity, damage);
+    }
+}
+
+/// Apply every queued hit, respecting invulnerability frames
+pub fn damage_system(ecs: &mut HecsWorld) {
+    let mut attacked = Vec::new();
+    for (entity, (health, pending, velocity, effects, stats, dead)) in ecs
+        .query_mut::<(
+            &mut Health,
+            &mut PendingDamage,
+            Option<&mut Velocity>,
+            Option<&mut StatusEffects>,
+            Option<&Stats>,
+            Option<&Dead>,
+        )>()
+    {
+        health.invulnerable_ticks = health.invulnerable_ticks.saturating_sub(1);
+        if dead.is_some() {
+            pending.0.clear();
+            continue;
+        }
+        // the biggest hit wins, the rest land during the immunity it gives
+        let biggest = pending
+            .0
+            .drain(..)
+            .max_by(|a, b| a.amount.total_cmp(&b.amount));
+        let Some(biggest) = biggest else {
+            continue;
+        };
+        if biggest.amount > 0.0 && !health.is_invulnerable() {
+            let amount = match stats {
+                Some(stats) if biggest.source.is_blockable() => {
+                    stats.damage_taken(biggest.amount)
+                }
+                _ => biggest.amount,
+            };
+            health.damage(amount);
+            health.invulnerable_ticks = health.invulnerability;
+            if let Some(velocity) = velocity {
+                velocity.0 += biggest.knockback;
+            }
+            if let (Some(effects), Some(effect)) = (effects, biggest.effect) {
+                effects.apply(effect);
+            }
+            if let Some(attacker) = biggest.attacker {
+                attacked.push((entity, attacker));
+            }
+        }
+    }
+    for (entity, attacker) in attacked {
+        let _ = ecs.insert_one(entity, LastAttacker(attacker));
+    }
+}
+
+/// Players that run out of health drop their items and start respawning
+pub fn player_death_system(ecs: &mut HecsWorld) {
+    let mut died = Vec::new();
+    for (entity, (health, pos, inventory, config)) in
+        ecs.query_mut::<Without<
+            (&Health, &Position, &mut Inventory, &DeathConfig),
+            &Dead,
+        >>()
+        .with::<&Player>()
+    {
+        if !health.is_dead() {
+            continue;
+        }
+        let mut lost = Vec::new();
+        for index in 0..inventory.len() {
+            if inventory
+                .get_item(index)
+                .is_some_and(|item| config.item_loss.drops(item))
+            {
+                lost.extend(inventory.take_item(index));
+            }
+        }
+        died.push((entity, pos.0, lost, config.respawn_ticks));
+    }
+
+    for (entity, pos, lost, respawn_ticks) in died {
+        for item in lost {
+            spawn_dropped_item(ecs, item, pos, Vec2::new(0.0, -4.0));
+        }
+        let _ = ecs.insert_one(entity, Dead { respawn_ticks });
+    }
+}
+
+/// Count down dead players, then bring them back at their spawn point
+pub fn respawn_system(ecs: &mut HecsWorld) {
+    let mut respawned = Vec::new();
+    for (entity, (dead, health, pos, previous, velocity, spawn, effects)) in ecs
+        .query_mut::<(
+            &mut Dead,
+            &mut Health,
+            &mut Position,
+            Option<&mut PreviousPosition>,
+            &mut Velocity,
+            &SpawnPoint,
+            Option<&mut StatusEffects>,
+        )>()
+    {
+        dead.respawn_ticks = dead.respawn_ticks.saturating_sub(1);
+        if dead.respawn_ticks > 0 {
+            continue;
+        }
+        health.current = health.max;
+        health.invulnerable_ticks = health.invulnerability;
+        pos.0 = spawn.0;
+        // teleport, don't slide there
+        if let Some(previous) = previous {
+            previous.0 = spawn.0;
+        }
+        velocity.0 = Vec2::ZERO;
+        // a fresh start, nothing carries over
+        if let Some(effects) = effects {
+            effects.0.clear();
+        }
+        respawned.push(entity);
+    }
+    for entity in respawned {
+        let _ = ecs.remove_one::<Dead>(entity);
+    }
+}
@@ -0,0 +1,182 @@
+use crate::{
+    components::{
+        Collider, Gravity, Position, PreviousPosition, Sprite, Velocity,
+    },
+    inventory::Inventory,
+    item::Item,
+    physics::apply_friction,
+    player::Player,
+    utils::can_stack,
+};
+use glam::Vec2;
+use hecs::{Entity, World as HecsWorld};
+
+/// An item lying around in the world, waiting to be picked up
+#[derive(Clone, Copy, Debug)]
+pub struct DroppedItem {
+    pub item: Item,
+    pub age: u64,          // ticks since it was dropped
+    pub pickup_delay: u64, // ticks before the player can pick it up
+}
+
+impl DroppedItem {
+    pub const SIZE: Vec2 = Vec2 { x: 0.4, y: 0.4 };
+    const DESPAWN_TICKS: u64 = 60 * 60 * 5; // 5 minutes at 60 ticks/s
+    const PICKUP_RADIUS: f32 = 0.75;
+    const MAGNET_RADIUS: f32 = 3.0;
+    const MAGNET_SPEED: f32 = 12.0;
+    const MERGE_RADIUS: f32 = 1.0;
+    const FRICTION: f32 = 10.0;
+
+    #[must_use]
+    pub fn new(item: Item) -> Self {
+        Self {
+            item,
+            age: 0,
+            pickup_delay: 0,
+        }
+    }
+}
+
+pub fn spawn_dropped_item(
+    ecs: &mut HecsWorld,
+    item: Item,
+    pos: Vec2,
+    velocity: Vec2,
+) -> Entity {
+    ecs.spawn((
+        DroppedItem::new(item),
+        Position(pos),
+        PreviousPosition(pos),
+        Velocity(velocity),
+        Collider::new(DroppedItem::SIZE),
+        Gravity::default(),
+        Sprite { color: item.color },
+    ))
+}
+
+/// Throw the player's held stack out in front of them
+pub fn throw_held_item(ecs: &mut HecsWorld, player: Entity) {
+    const THROW_SPEED: Vec2 = Vec2 { x: 8.0, y: -6.0 };
+    const THROW_PICKUP_DELAY: u64 = 90;
+
+    let Ok((player, inventory, pos)) =
+        ecs.query_one_mut::<(&Player, &mut Inventory, &Position)>(player)
+    else {
+        return;
+    };
+    let Some(item) = inventory.take_item(player.active_inventory_slot) else {
+        return;
+    };
+    let facing = match player.look_dir.as_ivec2().x {
+        0 => 1.0,
+        x => x as f32,
+    };
+    let velocity = Vec2::new(THROW_SPEED.x * facing, THROW_SPEED.y);
+    let pos = pos.center(Player::SIZE) - DroppedItem::SIZE / 2.0;
+
+    let entity = spawn_dropped_item(ecs, item, pos, velocity);
+    if let Ok(mut dropped) = ecs.get::<&mut DroppedItem>(entity) {
+        dropped.pickup_delay = THROW_PICKUP_DELAY;
+    }
+}
+
+/// Merge, attract, pick up and despawn every dropped item.
+/// Moving them is left to the shared physics systems
+pub fn update_dropped_items(ecs: &mut HecsWorld, player: Entity, fps: f32) {
+    let mut despawn: Vec<Entity> = Vec::new();
+
+    let Ok(player_center) = ecs
+        .get::<&Position>(player)
+        .map(|pos| pos.center(Player::SIZE))
+    else {
+        return;
+    };
+    let Ok(mut inventory) = ecs.get::<&mut Inventory>(player) else {
+        return;
+    };
+
+    for (entity, (dropped, pos, velocity, collider)) in ecs
+        .query::<(&mut DroppedItem, &Position, &mut Velocity, &Collider)>()
+        .iter()
+    {
+        dropped.age += 1;
+        dropped.pickup_delay = dropped.pickup_delay.saturating_sub(1);
+        if dropped.age >= DroppedItem::DESPAWN_TICKS {
+            despawn.push(entity);
+            continue;
+        }
+
+        if collider.on_ground() {
+            apply_friction(&mut velocity.0, DroppedItem::FRICTION, fps);
+        }
+
+        let to_player = player_center - pos.center(collider.size);
+        let distance = to_player.length();
+        if dropped.pickup_delay == 0 {
+            if distance <= DroppedItem::PICKUP_RADIUS {
+                // whatever doesn't fit stays on the ground
+                dropped.item.amount = inventory.add_item(dropped.item);
+                if dropped.item.amount == 0 {
+                    despawn.push(entity);
+                    continue;
+                }
+            } else if distance <= DroppedItem::MAGNET_RADIUS {
+                velocity.0 = to_player / distance * DroppedItem::MAGNET_SPEED;
+            }
+        }
+    }
+    drop(inventory);
+
+    merge_dropped_items(ecs, &mut despawn);
+
+    for entity in despawn {
+        // already gone is fine
+        let _ = ecs.despawn(entity);
+    }
+}
+
+/// Fold nearby identical stacks into each other, as long as they fit
+fn merge_dropped_items(ecs: &mut HecsWorld, despawn: &mut Vec<Entity>) {
+    let mut items: Vec<(Entity, DroppedItem, Vec2)> = ecs
+        .query_mut::<(&DroppedItem, &Position)>()
+        .into_iter()
+        .filter(|(entity, _)| !despawn.contains(entity))
+        .map(|(entity, (dropped, pos))| (entity, *dropped, pos.0))
+        .collect();
+    let mut merged = vec![false; items.len()];
+
+    for i in 0..items.len() {
+        if merged[i] {
+            continue;
+        }
+        for j in i + 1..items.len() {
+            let (a, b) = (&items[i], &items[j]);
+            if merged[j]
+                || !can_stack(&a.1.item, &b.1.item)
+                || a.1.item.amount + b.1.item.amount > a.1.item.max_stack
+                || a.2.distance(b.2) > DroppedItem::MERGE_RADIUS
+            {
+                continue;
+            }
+            let amount = b.1.item.amount;
+            let pickup_delay = a.1.pickup_delay.max(b.1.pickup_delay);
+            // the merged stack is as fresh as its newest part
+            let age = a.1.age.min(b.1.age);
+            items[i].1.item.amount += amount;
+            items[i].1.pickup_delay = pickup_delay;
+            items[i].1.age = age;
+            merged[j] = true;
+            despawn.push(items[j].0);
+        }
+    }
+
+    for (i, (entity, dropped, _)) in items.into_iter().enumerate() {
+        if merged[i] {
+            continue;
+        }
+        if let Ok(mut current) = ecs.get::<&mut DroppedItem>(entity) {
+            *current = dropped;
+        }
+    }
+}
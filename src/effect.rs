@@ -0,0 +1,138 @@
+use crate::{components::Health, damage::Dead};
+use hecs::{Entity, Without, World as HecsWorld};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EffectName {
+    Poison,
+    Regeneration,
+    Speed,
+    MiningHaste,
+    FeatherFall,
+}
+
+/// What happens to the level when an effect that is already active is
+/// applied again
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StackPolicy {
+    /// keep the higher of the two levels
+    Highest,
+    /// add the levels together, up to `max`
+    Add { max: u32 },
+}
+
+/// What happens to the remaining time when an effect that is already
+/// active is applied again
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RefreshPolicy {
+    /// keep whichever has longer left
+    Longest,
+    /// add the new duration on top
+    Extend,
+    /// leave the timer alone
+    Keep,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StatusEffect {
+    pub name: EffectName,
+    pub level: u32,
+    pub strength: f32, // per level, what it means depends on `name`
+    pub duration: u32, // ticks it lasted when applied, for the HUD
+    pub remaining: u32, // ticks left
+    pub stack: StackPolicy,
+    pub refresh: RefreshPolicy,
+    pub color: (u8, u8, u8), // HUD icon
+}
+
+impl StatusEffect {
+    /// `strength` scaled by `level`
+    #[must_use]
+    pub fn total_strength(&self) -> f32 {
+        self.strength * self.level as f32
+    }
+
+    /// How much of the duration is left, 1 is all of it
+    #[must_use]
+    pub fn remaining_percent(&self) -> f32 {
+        if self.duration == 0 {
+            return 0.0;
+        }
+        self.remaining as f32 / self.duration as f32
+    }
+
+    /// Fold `other` (the same effect, applied again) into this one
+    fn merge(&mut self, other: &StatusEffect) {
+        self.level = match self.stack {
+            StackPolicy::Highest => self.level.max(other.level),
+            StackPolicy::Add { max } => (self.level + other.level).min(max),
+        };
+        self.remaining = match self.refresh {
+            RefreshPolicy::Longest => self.remaining.max(other.remaining),
+            RefreshPolicy::Extend => self.remaining + other.remaining,
+            RefreshPolicy::Keep => self.remaining,
+        };
+        self.duration = self.duration.max(self.remaining);
+    }
+}
+
+/// Every effect active on an entity, at most one of each name
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StatusEffects(pub Vec<StatusEffect>);
+
+impl StatusEffects {
+    pub fn apply(&mut self, effect: StatusEffect) {
+        match self.0.iter_mut().find(|e| e.name == effect.name) {
+            Some(active) => active.merge(&effect),
+            None => self.0.push(effect),
+        }
+    }
+
+    /// Apply `effect` if it isn't active yet, otherwise only top its timer
+    /// up. For sources that apply every tick, so they don't stack forever
+    pub fn keep_applied(&mut self, effect: StatusEffect) {
+        match self.0.iter_mut().find(|e| e.name == effect.name) {
+            Some(active) => {
+                active.remaining = active.remaining.max(effect.remaining);
+                active.duration = active.duration.max(active.remaining);
+            }
+            None => self.0.push(effect),
+        }
+    }
+
+    #[must_use]
+    pub fn get(&self, name: EffectName) -> Option<&StatusEffect> {
+        self.0.iter().find(|e| e.name == name)
+    }
+
+    /// Total strength of `name`, 0 if it isn't active
+    #[must_use]
+    pub fn strength(&self, name: EffectName) -> f32 {
+        self.get(name).map_or(0.0, StatusEffect::total_strength)
+    }
+}
+
+/// Apply `effect` to `entity`, if it can have effects
+pub fn apply_effect(ecs: &mut HecsWorld, entity: Entity, effect: StatusEffect) {
+    if let Ok(mut effects) = ecs.get::<&mut StatusEffects>(entity) {
+        effects.apply(effect);
+    }
+}
+
+/// Run damage and healing over time, then count every effect down
+pub fn status_effect_system(ecs: &mut HecsWorld, fps: f32) {
+    // the dead are frozen until they respawn
+    for (_, (effects, health)) in ecs
+        .query_mut::<Without<(&mut StatusEffects, Option<&mut Health>), &Dead>>(
+        )
+    {
+        if let Some(health) = health {
+            // straight to health, so i-frames don't eat the ticks
+            health.damage(effects.strength(EffectName::Poison) / fps);
+            health.heal(effects.strength(EffectName::Regeneration) / fps);
+        }
+        for effect in &mut effects.0 {
+            effect.remaining = effect.remaining.saturating_sub(1);
+        }
+        effects.0.retain(|effect| effect.remaining > 0);
+    }
+}
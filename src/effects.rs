@@ -0,0 +1,91 @@
+use crate::effect::{EffectName, RefreshPolicy, StackPolicy, StatusEffect};
+
+#[must_use]
+pub fn effect_from_name(
+    name: EffectName,
+    duration: u32,
+    level: u32,
+) -> StatusEffect {
+    match name {
+        EffectName::Poison => effect_poison(duration, level),
+        EffectName::Regeneration => effect_regeneration(duration, level),
+        EffectName::Speed => effect_speed(duration, level),
+        EffectName::MiningHaste => effect_mining_haste(duration, level),
+        EffectName::FeatherFall => effect_feather_fall(duration, level),
+    }
+}
+
+/// Health per second lost, per level. Doses stack up
+#[must_use]
+pub const fn effect_poison(duration: u32, level: u32) -> StatusEffect {
+    StatusEffect {
+        name: EffectName::Poison,
+        level,
+        strength: 4.0,
+        duration,
+        remaining: duration,
+        stack: StackPolicy::Add { max: 5 },
+        refresh: RefreshPolicy::Longest,
+        color: (0, 150, 0),
+    }
+}
+
+/// Health per second gained, per level
+#[must_use]
+pub const fn effect_regeneration(duration: u32, level: u32) -> StatusEffect {
+    StatusEffect {
+        name: EffectName::Regeneration,
+        level,
+        strength: 3.0,
+        duration,
+        remaining: duration,
+        stack: StackPolicy::Highest,
+        refresh: RefreshPolicy::Longest,
+        color: (255, 105, 180),
+    }
+}
+
+/// Extra move speed per level, 0.25 is 25% faster
+#[must_use]
+pub const fn effect_speed(duration: u32, level: u32) -> StatusEffect {
+    StatusEffect {
+        name: EffectName::Speed,
+        level,
+        strength: 0.25,
+        duration,
+        remaining: duration,
+        stack: StackPolicy::Highest,
+        refresh: RefreshPolicy::Longest,
+        color: (0, 191, 255),
+    }
+}
+
+/// Extra mining damage per level. More doses last longer
+#[must_use]
+pub const fn effect_mining_haste(duration: u32, level: u32) -> StatusEffect {
+    StatusEffect {
+        name: EffectName::MiningHaste,
+        level,
+        strength: 0.5,
+        duration,
+        remaining: duration,
+        stack: StackPolicy::Highest,
+        refresh: RefreshPolicy::Extend,
+        color: (255, 215, 0),
+    }
+}
+
+/// Less fall damage per level, 0.5 halves it
+#[must_use]
+pub const fn effect_feather_fall(duration: u32, level: u32) -> StatusEffect {
+    StatusEffect {
+        name: EffectName::FeatherFall,
+        level,
+        strength: 0.5,
+        duration,
+        remaining: duration,
+        stack: StackPolicy::Highest,
+        refresh: RefreshPolicy::Longest,
+        color: (200, 230, 255),
+    }
+}
@@ -0,0 +1,106 @@
+use glam::Vec2;
+use hecs::{Entity, World as HecsWorld};
+
+use crate::{
+    components::{
+        Collider, Gravity, Health, Position, PreviousPosition, Sprite, Velocity,
+    },
+    damage::{Damage, DamageSource, PendingDamage},
+    effect::StatusEffects,
+    enemy::{Behaviour, Enemy, EnemyName, Locomotion},
+    loot_tables::{LOOT_BAT, LOOT_CRAWLER},
+    movement::MovementConfig,
+    pathfinding::{PathConfig, PathFollower},
+    stats::Stats,
+};
+
+pub fn spawn_enemy_from_name(
+    ecs: &mut HecsWorld,
+    name: EnemyName,
+    pos: Vec2,
+) -> Entity {
+    match name {
+        EnemyName::Crawler => spawn_crawler(ecs, pos),
+        EnemyName::Bat => spawn_bat(ecs, pos),
+    }
+}
+
+/// Slow ground walker that hits hard up close
+pub fn spawn_crawler(ecs: &mut HecsWorld, pos: Vec2) -> Entity {
+    ecs.spawn((
+        Enemy {
+            name: EnemyName::Crawler,
+            locomotion: Locomotion::Walker,
+            behaviour: Behaviour::Idle,
+            behaviour_ticks: 0,
+            wander_dir: Vec2::ZERO,
+            sight_range: 12.0,
+            attack_range: 1.0,
+            attack: Damage::new(15.0, DamageSource::Mob),
+            attack_delay: 60,
+            attack_cooldown: 0,
+            loot: Some(&LOOT_CRAWLER),
+            xp: 12,
+        },
+        Position(pos),
+        PreviousPosition(pos),
+        Velocity::default(),
+        Collider::new(Vec2::new(0.9, 0.9)),
+        Gravity::default(),
+        MovementConfig {
+            max_speed: 6.0,
+            acceleration: 40.0,
+            jump_velocity: 10.0, // a bit over one block
+            ..Default::default()
+        },
+        PathFollower::new(PathConfig::default()),
+        Health::new(60.0).with_invulnerability(10),
+        StatusEffects::default(),
+        Stats::default(),
+        PendingDamage::default(),
+        Sprite {
+            color: (76, 153, 0),
+        },
+    ))
+}
+
+/// Fast, fragile flyer that ignores gravity
+pub fn spawn_bat(ecs: &mut HecsWorld, pos: Vec2) -> Entity {
+    ecs.spawn((
+        Enemy {
+            name: EnemyName::Bat,
+            locomotion: Locomotion::Flyer,
+            behaviour: Behaviour::Idle,
+            behaviour_ticks: 0,
+            wander_dir: Vec2::ZERO,
+            sight_range: 16.0,
+            attack_range: 0.8,
+            attack: Damage::new(8.0, DamageSource::Mob),
+            attack_delay: 45,
+            attack_cooldown: 0,
+            loot: Some(&LOOT_BAT),
+            xp: 6,
+        },
+        Position(pos),
+        PreviousPosition(pos),
+        Velocity::default(),
+        Collider::new(Vec2::new(0.6, 0.4)),
+        MovementConfig {
+            max_speed: 9.0,
+            acceleration: 30.0,
+            friction: 20.0,
+            ..Default::default()
+        },
+        PathFollower::new(PathConfig {
+            flying: true,
+            ..Default::default()
+        }),
+        Health::new(25.0).with_invulnerability(10),
+        StatusEffects::default(),
+        Stats::default(),
+        PendingDamage::default(),
+        Sprite {
+            color: (60, 40, 70),
+        },
+    ))
+}
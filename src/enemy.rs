@@ -0,0 +1,333 @@
+use crate::{
+    components::{Collider, Health, Position, Velocity},
+    damage::{Damage, Dead, deal_damage},
+    dropped_item::{DroppedItem, spawn_dropped_item},
+    enemies::spawn_enemy_from_name,
+    loot::LootTable,
+    movement::{MovementConfig, try_move},
+    pathfinding::{PathFollower, feet_tile},
+    physics::Contacts,
+    stats::{StatName, Stats},
+    utils::Direction,
+    world::World,
+};
+use glam::Vec2;
+use hecs::{Entity, Without, World as HecsWorld};
+use rand::Rng;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum EnemyName {
+    #[default]
+    Crawler,
+    Bat,
+}
+
+/// How an enemy gets around
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Locomotion {
+    Walker, // falls, and jumps up 1-block steps
+    Flyer,  // no gravity, moves in any direction
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Behaviour {
+    #[default]
+    Idle,
+    Wander,
+    Chase,
+    Attack,
+}
+
+/// A hostile entity, and what its AI is doing right now
+#[derive(Clone, Copy, Debug)]
+pub struct Enemy {
+    pub name: EnemyName,
+    pub locomotion: Locomotion,
+    pub behaviour: Behaviour,
+    pub behaviour_ticks: u32, // ticks left before idle/wander is re-rolled
+    pub wander_dir: Vec2,
+    pub sight_range: f32, // starts chasing the target inside this
+    pub attack_range: f32, // distance between centers to hit from
+    pub attack: Damage,
+    pub attack_delay: u32, // ticks between attacks
+    pub attack_cooldown: u32,
+    pub loot: Option<&'static LootTable>,
+    pub xp: u32, // given to whoever kills it
+}
+
+/// Outside the active chunks; physics and AI leave it alone
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Asleep {
+    pub ticks: u32, // since it fell asleep
+}
+
+impl Asleep {
+    /// Left behind this long, it is despawned so far off areas don't
+    /// pile up enemies forever
+    pub const DESPAWN_TICKS: u32 = 60 * 60;
+}
+
+impl Enemy {
+    const MIN_IDLE_TICKS: u32 = 60;
+    const MAX_IDLE_TICKS: u32 = 60 * 4;
+
+    /// Pick the next behaviour from the distance to the target, if any
+    fn think(&mut self, distance: Option<f32>, rng: &mut impl Rng) {
+        self.attack_cooldown = self.attack_cooldown.saturating_sub(1);
+        match distance {
+            Some(d) if d <= self.attack_range => {
+                self.behaviour = Behaviour::Attack;
+            }
+            Some(d) if d <= self.sight_range => {
+                self.behaviour = Behaviour::Chase;
+            }
+            _ => {
+                let lost_target = matches!(
+                    self.behaviour,
+                    Behaviour::Chase | Behaviour::Attack
+                );
+                self.behaviour_ticks = self.behaviour_ticks.saturating_sub(1);
+                if lost_target || self.behaviour_ticks == 0 {
+                    self.roll_idle(rng);
+                }
+            }
+        }
+    }
+
+    /// Either stand still or wander off somewhere, for a while
+    fn roll_idle(&mut self, rng: &mut impl Rng) {
+        self.behaviour_ticks =
+            rng.random_range(Self::MIN_IDLE_TICKS..=Self::MAX_IDLE_TICKS);
+        if rng.random::<bool>() {
+            self.behaviour = Behaviour::Idle;
+            return;
+        }
+        self.behaviour = Behaviour::Wander;
+        let x = if rng.random::<bool>() { 1.0 } else { -1.0 };
+        self.wander_dir = match self.locomotion {
+            Locomotion::Walker => Vec2::new(x, 0.0),
+            Locomotion::Flyer => {
+                Vec2::new(x, rng.random_range(-0.5..=0.5)).normalize()
+            }
+        };
+    }
+
+    /// Which way the enemy wants to go this tick.
+    /// `to_target` is the next waypoint if it has a path, else the target
+    fn desired_dir(&self, to_target: Vec2) -> Vec2 {
+        match self.behaviour {
+            Behaviour::Idle | Behaviour::Attack => Vec2::ZERO,
+            Behaviour::Wander => self.wander_dir,
+            Behaviour::Chase => match self.locomotion {
+                Locomotion::Walker => Vec2::new(to_target.x.signum(), 0.0),
+                Locomotion::Flyer => to_target.normalize_or_zero(),
+            },
+        }
+    }
+}
+
+/// Put enemies outside the active chunks to sleep, and wake the rest.
+/// Ones that stay asleep too long are despawned
+pub fn enemy_sleep_system(ecs: &mut HecsWorld, map: &World) {
+    let mut changes = Vec::new();
+    let mut despawn = Vec::new();
+    for (entity, (pos, asleep)) in ecs
+        .query_mut::<(&Position, Option<&mut Asleep>)>()
+        .with::<&Enemy>()
+    {
+        let active = map.is_active(pos.0);
+        match (asleep, active) {
+            (Some(asleep), false) => {
+                asleep.ticks += 1;
+                if asleep.ticks >= Asleep::DESPAWN_TICKS {
+                    despawn.push(entity);
+                }
+            }
+            (Some(_), true) | (None, false) => changes.push((entity, active)),
+            (None, true) => {}
+        }
+    }
+    for entity in despawn {
+        let _ = ecs.despawn(entity);
+    }
+    for (entity, active) in changes {
+        if active {
+            let _ = ecs.remove_one::<Asleep>(entity);
+        } else {
+            let _ = ecs.insert_one(entity, Asleep::default());
+        }
+    }
+}
+
+/// Decide what every awake enemy does, steer it, and attack `target`
+pub fn enemy_ai_system(
+    ecs: &mut HecsWorld,
+    map: &mut World,
+    target_entity: Entity,
+    fps: f32,
+) {
+    // a dead target can't be chased
+    let target = ecs
+        .query_one_mut::<Without<(&Position, &Collider), &Dead>>(target_entity)
+        .ok()
+        .map(|(pos, collider)| {
+            (pos.center(collider.size), feet_tile(pos.0, collider.size))
+        });
+
+    let mut attacks = Vec::new();
+    for (entity, (enemy, pos, velocity, collider, config, follower, stats)) in
+        ecs.query_mut::<Without<
+            (
+                &mut Enemy,
+                &Position,
+                &mut Velocity,
+                &Collider,
+                &MovementConfig,
+                Option<&mut PathFollower>,
+                Option<&Stats>,
+            ),
+            &Asleep,
+        >>()
+    {
+        let center = pos.center(collider.size);
+        let to_target =
+            target.map_or(Vec2::ZERO, |(target, _)| target - center);
+        enemy.think(target.map(|_| to_target.length()), map.rng());
+
+        if enemy.behaviour == Behaviour::Attack && enemy.attack_cooldown == 0 {
+            enemy.attack_cooldown = enemy.attack_delay;
+            attacks.push(enemy.attack.with_attacker(entity));
+        }
+
+        // go around terrain when there is a known way, straight at it if not
+        let waypoint = match (enemy.behaviour, follower, target) {
+            (Behaviour::Chase, Some(follower), Some((_, goal))) => {
+                follower.next_step(map, feet_tile(pos.0, collider.size), goal)
+            }
+            _ => None,
+        };
+        let speed = stats.map_or(1.0, |stats| stats.get(StatName::MoveSpeed));
+        let dir = enemy.desired_dir(waypoint.map_or(to_target, |tile| {
+            tile.as_vec2() + Vec2::splat(0.5) - center
+        })) * speed;
+        match enemy.locomotion {
+            Locomotion::Walker => {
+                walk(velocity, collider, config, map, pos.0, dir.x, fps);
+            }
+            Locomotion::Flyer => fly(velocity, config, dir, fps),
+        }
+    }
+
+    for attack in attacks {
+        deal_damage(ecs, target_entity, attack);
+    }
+}
+
+/// Run towards `dir_x`, hopping up a step if one is in the way.
+/// How big `dir_x` is scales the speed, 1 is normal
+fn walk(
+    velocity: &mut Velocity,
+    collider: &Collider,
+    config: &MovementConfig,
+    map: &World,
+    pos: Vec2,
+    dir_x: f32,
+    fps: f32,
+) {
+    let direction = if dir_x < 0.0 {
+        Direction::Left
+    } else if dir_x > 0.0 {
+        Direction::Right
+    } else {
+        Direction::None
+    };
+    try_move(velocity, config, direction, dir_x.abs(), fps);
+
+    let blocked = collider.contacts.intersects(if dir_x < 0.0 {
+        Contacts::LEFT
+    } else {
+        Contacts::RIGHT
+    });
+    // only a 1-block step, anything taller is a wall
+    let step_is_clear =
+        !map.collides(pos + Vec2::new(dir_x.signum(), -1.0), collider.size);
+    if dir_x != 0.0 && blocked && collider.on_ground() && step_is_clear {
+        velocity.0.y = -config.jump_velocity;
+    }
+}
+
+/// Accelerate towards `dir` (scaled by its length), or drift to a stop
+/// if it is zero
+fn fly(velocity: &mut Velocity, config: &MovementConfig, dir: Vec2, fps: f32) {
+    let desired = dir * config.max_speed;
+    let change = desired - velocity.0;
+    let max_change = if dir == Vec2::ZERO {
+        config.friction / fps
+    } else {
+        config.acceleration / fps
+    };
+    velocity.0 += change.clamp_length_max(max_change);
+}
+
+/// Drop the loot of every enemy that died this tick, before it despawns
+pub fn enemy_loot_system(ecs: &mut HecsWorld, map: &mut World) {
+    let mut drops = Vec::new();
+    for (_, (enemy, health, pos, collider)) in
+        ecs.query_mut::<(&Enemy, &Health, &Position, &Collider)>()
+    {
+        if !health.is_dead() {
+            continue;
+        }
+        let Some(loot) = enemy.loot else {
+            continue;
+        };
+        let pos = pos.center(collider.size) - DroppedItem::SIZE / 2.0;
+        for item in loot.roll(map.loot_rng(), 1.0) {
+            drops.push((pos, item));
+        }
+    }
+    for (pos, item) in drops {
+        spawn_dropped_item(ecs, item, pos, Vec2::new(0.0, -4.0));
+    }
+}
+
+/// Now and then spawn an enemy somewhere in the active chunks, out of
+/// sight of `player`. Crawlers need ground to stand on, bats open air
+pub fn enemy_spawn_system(
+    ecs: &mut HecsWorld,
+    map: &mut World,
+    player: Entity,
+) {
+    const SPAWN_CHANCE: f64 = 1.0 / 120.0; // per tick
+    // awake ones, left behind ones don't count and despawn in time
+    const MAX_ENEMIES: usize = 8;
+    const MIN_PLAYER_DISTANCE: f32 = 20.0;
+    const SIZE: Vec2 = Vec2::ONE; // big enough for every enemy
+
+    if !map.rng().random_bool(SPAWN_CHANCE)
+        || ecs
+            .query_mut::<Without<&Enemy, &Asleep>>()
+            .into_iter()
+            .count()
+            >= MAX_ENEMIES
+    {
+        return;
+    }
+    let Ok(player_pos) = ecs.get::<&Position>(player).map(|pos| pos.0) else {
+        return;
+    };
+    let Some(tile) = map.random_active_tile() else {
+        return;
+    };
+    let pos = tile.as_vec2();
+    if pos.distance(player_pos) < MIN_PLAYER_DISTANCE || map.collides(pos, SIZE)
+    {
+        return;
+    }
+    let name = if map.collides(pos + Vec2::Y, SIZE) {
+        EnemyName::Crawler
+    } else {
+        EnemyName::Bat
+    };
+    spawn_enemy_from_name(ecs, name, pos);
+}
@@ -0,0 +1,113 @@
+use crate::item::{EquipmentKind, Item};
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EquipmentSlot {
+    Head,
+    Chest,
+    Legs,
+    Accessory1,
+    Accessory2,
+}
+
+impl EquipmentSlot {
+    pub const ALL: [EquipmentSlot; 5] = [
+        EquipmentSlot::Head,
+        EquipmentSlot::Chest,
+        EquipmentSlot::Legs,
+        EquipmentSlot::Accessory1,
+        EquipmentSlot::Accessory2,
+    ];
+
+    #[must_use]
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// The kind of item that goes in this slot
+    #[must_use]
+    pub fn kind(self) -> EquipmentKind {
+        match self {
+            EquipmentSlot::Head => EquipmentKind::Head,
+            EquipmentSlot::Chest => EquipmentKind::Chest,
+            EquipmentSlot::Legs => EquipmentKind::Legs,
+            EquipmentSlot::Accessory1 | EquipmentSlot::Accessory2 => {
+                EquipmentKind::Accessory
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EquipError {
+    NotEquipment,
+    WrongSlot {
+        slot: EquipmentSlot,
+        kind: EquipmentKind,
+    },
+}
+
+impl fmt::Display for EquipError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EquipError::NotEquipment => write!(f, "item can't be equipped"),
+            EquipError::WrongSlot { slot, kind } => {
+                write!(f, "a {kind:?} item doesn't go in the {slot:?} slot")
+            }
+        }
+    }
+}
+
+impl std::error::Error for EquipError {}
+
+/// What the entity is wearing, one item per slot
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Equipment {
+    slots: [Option<Item>; EquipmentSlot::ALL.len()],
+}
+
+impl Equipment {
+    #[must_use]
+    pub fn get(&self, slot: EquipmentSlot) -> Option<&Item> {
+        self.slots[slot.index()].as_ref()
+    }
+
+    /// Every slot with what is in it
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<Item = (EquipmentSlot, Option<&Item>)> + '_ {
+        EquipmentSlot::ALL
+            .into_iter()
+            .map(|slot| (slot, self.get(slot)))
+    }
+
+    /// The slot `item` should go in: the first of its kind that is free,
+    /// or the first of its kind if they are all taken
+    #[must_use]
+    pub fn slot_for(&self, item: &Item) -> Option<EquipmentSlot> {
+        let kind = item.equipment_kind()?;
+        let mut fitting = EquipmentSlot::ALL
+            .into_iter()
+            .filter(|slot| slot.kind() == kind);
+        let first = fitting.clone().next();
+        fitting.find(|slot| self.get(*slot).is_none()).or(first)
+    }
+
+    /// Put `item` in `slot`. Returns whatever was in there before
+    pub fn equip(
+        &mut self,
+        slot: EquipmentSlot,
+        item: Item,
+    ) -> Result<Option<Item>, EquipError> {
+        let kind = item.equipment_kind().ok_or(EquipError::NotEquipment)?;
+        if kind != slot.kind() {
+            return Err(EquipError::WrongSlot { slot, kind });
+        }
+        Ok(self.slots[slot.index()].replace(item))
+    }
+
+    /// Empty `slot`, returning what was in it
+    pub fn unequip(&mut self, slot: EquipmentSlot) -> Option<Item> {
+        self.slots[slot.index()].take()
+    }
+}
@@ -0,0 +1,85 @@
+use crate::{
+    components::Health, damage::LastAttacker, enemy::Enemy, player::Player,
+};
+use hecs::{Entity, World as HecsWorld};
+
+/// XP, the level it adds up to, and skill points to spend
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Experience {
+    pub level: u32,
+    pub xp: u32,           // towards the next level
+    pub skill_points: u32, // unspent
+}
+
+impl Default for Experience {
+    fn default() -> Self {
+        Self {
+            level: 1,
+            xp: 0,
+            skill_points: 0,
+        }
+    }
+}
+
+impl Experience {
+    const XP_PER_LEVEL: u32 = 20; // level 1 needs 20, level 2 needs 40, ...
+    const LEVELS_PER_TIER: u32 = 5;
+    const SKILL_POINTS_PER_LEVEL: u32 = 1;
+
+    /// XP needed to go from the current level to the next
+    #[must_use]
+    pub fn xp_to_next_level(&self) -> u32 {
+        Self::XP_PER_LEVEL * self.level
+    }
+
+    /// How far into the current level we are, 1 is levelling up
+    #[must_use]
+    pub fn progress(&self) -> f32 {
+        self.xp as f32 / self.xp_to_next_level() as f32
+    }
+
+    /// The highest `Block::required_level` we are skilled enough to mine.
+    /// Goes up every few levels
+    #[must_use]
+    pub fn mining_tier(&self) -> u32 {
+        1 + self.level / Self::LEVELS_PER_TIER
+    }
+
+    /// Returns how many levels were gained
+    pub fn add_xp(&mut self, amount: u32) -> u32 {
+        self.xp += amount;
+        let mut gained = 0;
+        while self.xp >= self.xp_to_next_level() {
+            self.xp -= self.xp_to_next_level();
+            self.level += 1;
+            self.skill_points += Self::SKILL_POINTS_PER_LEVEL;
+            gained += 1;
+        }
+        gained
+    }
+}
+
+/// Give `entity` `amount` XP, if it can level up
+pub fn grant_xp(ecs: &mut HecsWorld, entity: Entity, amount: u32) {
+    if amount == 0 {
+        return;
+    }
+    if let Ok(mut player) = ecs.get::<&mut Player>(entity) {
+        player.experience.add_xp(amount);
+    }
+}
+
+/// Reward whoever landed the last hit on every enemy that died this tick
+pub fn enemy_xp_system(ecs: &mut HecsWorld) {
+    let mut rewards = Vec::new();
+    for (_, (enemy, health, attacker)) in
+        ecs.query_mut::<(&Enemy, &Health, &LastAttacker)>()
+    {
+        if health.is_dead() {
+            rewards.push((attacker.0, enemy.xp));
+        }
+    }
+    for (entity, amount) in rewards {
+        grant_xp(ecs, entity, amount);
+    }
+}
@@ -1,13 +1,94 @@
-use crate::render::{Rect, draw_rect_with_hole};
+use crate::combat::{ground_slam, melee_attack};
+use crate::components::{
+    Collider, Health, Position, PreviousPosition, Velocity,
+};
+use crate::damage::{
+    Dead, damage_system, fall_damage_system, hazard_system,
+    player_death_system, respawn_system,
+};
+use crate::dropped_item::{
+    DroppedItem, spawn_dropped_item, throw_held_item, update_dropped_items,
+};
+use crate::effect::{StatusEffects, apply_effect, status_effect_system};
+use crate::enemy::{
+    enemy_ai_system, enemy_loot_system, enemy_sleep_system, enemy_spawn_system,
+};
+use crate::equipment::{Equipment, EquipmentSlot};
+use crate::experience::enemy_xp_system;
+use crate::inventory::Inventory;
+use crate::item::{Item, ItemAction, WeaponProps};
+use crate::items::{
+    item_backpack, item_feather_fall_potion, item_haste_potion,
+    item_leather_cap, item_leather_leggings, item_leather_tunic,
+    item_magic_wand, item_miners_charm, item_regeneration_potion,
+    item_runners_ring, item_speed_potion, item_stash, item_stone_spear,
+    item_venom_dagger, item_wooden_axe, item_wooden_bow, item_wooden_pickaxe,
+    item_wooden_shovel, item_wooden_sword,
+};
+use crate::movement::{JumpState, MovementConfig, try_move, update_jump};
+use crate::pathfinding::invalidate_paths_system;
+use crate::physics::boxes_overlap;
+use crate::player::{spawn_player, sync_inventory_size_system};
+use crate::projectile::{
+    Projectile, projectile_collision_system, projectile_flight_system,
+};
+use crate::projectiles::spawn_projectile_from_kind;
+use crate::render::{Rect, draw_rect_with_hole, draw_text};
+use crate::save::{SAVE_PATH, load_player, save_player};
+use crate::schedule::{Scheduler, Stage, System};
+use crate::skill::{Ability, SkillNode};
+use crate::skills::{SKILL_TREE, skill_from_id};
+use crate::stash::{StashSort, stash_in_reach};
+use crate::stats::{StatName, Stats, sync_stats_system};
+use crate::systems::{
+    death_system, gravity_system, interpolated_position, movement_system,
+    render_system,
+};
 use crate::{
-    block::Block, camera::Camera, input::Input, player::Player,
-    utils::Direction, world::World,
+    block::{Block, BlockName},
+    blocks::block_from_name,
+    camera::Camera,
+    input::{Input, KeyboardInput},
+    player::Player,
+    utils::Direction,
+    world::World,
 };
 use glam::{IVec2, Vec2};
-use hecs::World as HecsWorld;
-use sdl2::{EventPump, Sdl, VideoSubsystem, render::Canvas, video::Window};
+use hecs::{Entity, Without, World as HecsWorld};
+use sdl2::{
+    EventPump, Sdl, VideoSubsystem,
+    render::Canvas,
+    rwops::RWops,
+    ttf::{Font, Sdl2TtfContext},
+    video::Window,
+};
 use sdl2::{keyboard::Keycode, mouse::MouseButton};
-const FPS: f32 = 60.0;
+use std::path::Path;
+use std::time::{Duration, Instant};
+/// Simulation ticks per second, no matter how fast we render
+pub const TICK_RATE: f32 = 60.0;
+const TICK_DURATION: Duration =
+    Duration::from_nanos((1_000_000_000.0 / TICK_RATE) as u64);
+/// If a frame takes longer than this, the rest is dropped instead of being
+/// caught up on, so one slow frame can't snowball into more slow frames
+const MAX_FRAME_TIME: Duration = Duration::from_millis(250);
+const MAX_TICKS_PER_FRAME: u32 = 8;
+
+/// Everything the tick systems get to touch
+pub struct GameState {
+    pub map: World,
+    pub ecs: HecsWorld,
+    pub input: Input,
+    pub camera: Camera,
+    pub player: Entity,
+    pub tick_count: u64,
+    pub blocks: Vec<Block>, // every block in the active chunks, this tick
+    pub skill_panel_open: bool,
+    pub stash_open: bool,
+    pub stash_query: String, // only entries matching this are shown
+    pub stash_sort: StashSort,
+}
+
 pub struct Game {
     pub sdl_context: Sdl,
     pub video_subsystem: VideoSubsystem,
@@ -15,12 +96,869 @@ pub struct Game {
     pub canvas: Canvas<Window>,
     pub event_pump: EventPump,
 
-    pub map: World,
-    pub ecs: HecsWorld,
-    pub input: Input,
-    pub camera: Camera,
-    pub player: Player,
-    pub tick_count: u64,
+    pub state: GameState,
+    pub scheduler: Scheduler<GameState>,
+    pub debug_open: bool, // the system timings overlay, F3
+    pub font: Font<'static, 'static>,
+}
+
+type SystemResult = Result<(), Box<dyn std::error::Error>>;
+
+fn active_chunks_system(state: &mut GameState) -> SystemResult {
+    state.map.update_active_chunks(
+        state.camera.pos.x,
+        state.camera.pos.y,
+        state.camera.viewport_dims.x as i32,
+        state.camera.viewport_dims.y as i32,
+    );
+    state.blocks = state
+        .map
+        .get_active_chunks()
+        .iter()
+        .flat_map(|c| c.flatten())
+        .collect();
+    Ok(())
+}
+
+fn is_dead(state: &GameState) -> bool {
+    state.ecs.satisfies::<&Dead>(state.player).unwrap_or(true)
+}
+
+/// Which way the movement keys point, if any
+fn held_direction(keys: &KeyboardInput) -> Direction {
+    if keys.held.contains(&Keycode::Left) || keys.held.contains(&Keycode::A) {
+        Direction::Left
+    } else if keys.held.contains(&Keycode::Right)
+        || keys.held.contains(&Keycode::D)
+    {
+        Direction::Right
+    } else {
+        Direction::None
+    }
+}
+
+fn player_movement_system(state: &mut GameState) -> SystemResult {
+    if is_dead(state) {
+        return Ok(());
+    }
+    let keys = &state.input.keyboard;
+    let (player, velocity, collider, config, jump, stats) =
+        state.ecs.query_one_mut::<(
+            &Player,
+            &mut Velocity,
+            &Collider,
+            &MovementConfig,
+            &mut JumpState,
+            &Stats,
+        )>(state.player)?;
+    // a dash carries us on its own, and the stash has the keyboard
+    if player.skills.is_dashing() || state.stash_open {
+        return Ok(());
+    }
+
+    update_jump(
+        velocity,
+        collider,
+        config,
+        jump,
+        keys.pressed.contains(&Keycode::SPACE),
+        keys.held.contains(&Keycode::SPACE),
+        stats.get(StatName::JumpHeight),
+    );
+    try_move(
+        velocity,
+        config,
+        held_direction(keys),
+        stats.get(StatName::MoveSpeed),
+        TICK_RATE,
+    );
+    Ok(())
+}
+
+/// What `button` does with whatever the player is holding
+fn click_action(held: Option<&Item>, button: MouseButton) -> ItemAction {
+    match (held, button) {
+        (Some(item), MouseButton::Left) => item.primary_action(),
+        (Some(item), MouseButton::Right) => item.secondary_action(),
+        (None, MouseButton::Left) => ItemAction::Mine, // bare hands
+        _ => ItemAction::None,
+    }
+}
+
+/// Number keys pick a hotbar slot, the mouse wheel scrolls through them
+fn hotbar_system(state: &mut GameState) -> SystemResult {
+    const SLOT_KEYS: [Keycode; Player::HOTBAR_SIZE] = [
+        Keycode::Num1,
+        Keycode::Num2,
+        Keycode::Num3,
+        Keycode::Num4,
+        Keycode::Num5,
+        Keycode::Num6,
+        Keycode::Num7,
+        Keycode::Num8,
+        Keycode::Num9,
+        Keycode::Num0,
+    ];
+
+    let mut player = state.ecs.get::<&mut Player>(state.player)?;
+    for (index, key) in SLOT_KEYS.iter().enumerate() {
+        if state.input.keyboard.pressed.contains(key) {
+            player.select_slot(index);
+        }
+    }
+    // scrolling up goes left, like most games
+    player.scroll_slot(-state.input.mouse.wheel);
+    Ok(())
+}
+
+fn player_mining_system(state: &mut GameState) -> SystemResult {
+    if is_dead(state) {
+        return Ok(());
+    }
+    if !state.input.mouse.held.contains(&MouseButton::Left) {
+        return Ok(());
+    }
+    let (player, inventory, pos, collider, stats) =
+        state.ecs.query_one_mut::<(
+            &mut Player,
+            &Inventory,
+            &Position,
+            &Collider,
+            &Stats,
+        )>(state.player)?;
+
+    // weapons swing instead, see `player_attack_system`
+    if click_action(player.held_item(inventory), MouseButton::Left)
+        != ItemAction::Mine
+    {
+        return Ok(());
+    }
+    let mouse_pos = state.input.mouse.get_global_pos(&state.camera);
+    player.look_at(pos.center(collider.size), mouse_pos);
+
+    let reach = Player::MINING_REACH * stats.get(StatName::Reach);
+    if mouse_pos.distance_squared(pos.0) > reach * reach {
+        return Ok(());
+    }
+    let mined = player.hit_block(
+        IVec2::new(mouse_pos.x.floor() as i32, mouse_pos.y.floor() as i32),
+        &mut state.map,
+        inventory,
+        stats,
+        state.tick_count,
+    );
+    player.experience.add_xp(mined.xp);
+    for (block_pos, item) in mined.drops {
+        let pos =
+            block_pos.as_vec2() + Vec2::splat(0.5) - DroppedItem::SIZE / 2.0;
+        spawn_dropped_item(&mut state.ecs, item, pos, Vec2::new(0.0, -3.0));
+    }
+    Ok(())
+}
+
+fn player_attack_system(state: &mut GameState) -> SystemResult {
+    if is_dead(state) {
+        return Ok(());
+    }
+    if !state.input.mouse.held.contains(&MouseButton::Left) {
+        return Ok(());
+    }
+    let (player, inventory, pos, collider, stats) =
+        state.ecs.query_one_mut::<(
+            &mut Player,
+            &Inventory,
+            &Position,
+            &Collider,
+            &Stats,
+        )>(state.player)?;
+    let damage = stats.get(StatName::Damage);
+    let reach = stats.get(StatName::Reach);
+    let Some(held) = player.held_item(inventory).copied() else {
+        return Ok(());
+    };
+    if held.primary_action() != ItemAction::Attack {
+        return Ok(());
+    }
+    let delay = match (held.weapon_props(), held.ranged_props()) {
+        (Some(weapon), _) => weapon.cooldown_ticks(TICK_RATE),
+        (_, Some(ranged)) => ranged.cooldown_ticks(TICK_RATE),
+        _ => return Ok(()),
+    };
+
+    let center = pos.center(collider.size);
+    let mouse_pos = state.input.mouse.get_global_pos(&state.camera);
+    player.look_at(center, mouse_pos);
+
+    player.attack_cooldown.delay = delay;
+    if !player.attack_cooldown.try_use(state.tick_count) {
+        return Ok(());
+    }
+    let look_dir = player.look_dir;
+    if let Some(&weapon) = held.weapon_props() {
+        let weapon = WeaponProps {
+            damage: weapon.damage * damage,
+            reach: weapon.reach * reach,
+            ..weapon
+        };
+        melee_attack(&mut state.ecs, state.player, &weapon, look_dir);
+    } else if let Some(ranged) = held.ranged_props() {
+        let aim = (mouse_pos - center).normalize_or(Vec2::X);
+        let projectile = spawn_projectile_from_kind(
+            &mut state.ecs,
+            ranged.projectile,
+            state.player,
+            center,
+            aim * ranged.speed,
+        );
+        if let Ok(mut projectile) = state.ecs.get::<&mut Projectile>(projectile)
+        {
+            projectile.damage.amount *= damage;
+        }
+    }
+    Ok(())
+}
+
+/// Start any ability whose key was pressed, and play out the active one
+fn player_ability_system(state: &mut GameState) -> SystemResult {
+    const SLAM_DAMAGE: f32 = 30.0;
+    const SLAM_RADIUS: f32 = 3.0;
+    const SLAM_KNOCKBACK: f32 = 18.0;
+
+    if is_dead(state) {
+        return Ok(());
+    }
+    let keys = &state.input.keyboard;
+    let (player, velocity, collider, stats) =
+        state
+            .ecs
+            .query_one_mut::<(&mut Player, &mut Velocity, &Collider, &Stats)>(
+                state.player,
+            )?;
+    let damage = stats.get(StatName::Damage);
+
+    let landed = player.skills.update(collider);
+    // dash the way we are walking, or looking if we aren't
+    let dir_x = match (held_direction(keys), player.look_dir) {
+        (Direction::Left, _) | (Direction::None, Direction::Left) => -1.0,
+        _ => 1.0,
+    };
+    let abilities: Vec<Ability> = player.skills.abilities().collect();
+    for ability in abilities {
+        if player.skills.active.is_none()
+            && keys.pressed.contains(&ability.key)
+            && player.skills.is_ready(ability.name, state.tick_count)
+            && player.skills.start(ability.name, velocity, collider, dir_x)
+        {
+            player.skills.put_on_cooldown(
+                &ability,
+                state.tick_count,
+                TICK_RATE,
+            );
+        }
+    }
+
+    if landed {
+        ground_slam(
+            &mut state.ecs,
+            state.player,
+            SLAM_DAMAGE * damage,
+            SLAM_RADIUS,
+            SLAM_KNOCKBACK,
+        );
+    }
+    Ok(())
+}
+
+const SKILL_PANEL_X: f32 = 560.0;
+const SKILL_PANEL_Y: f32 = 10.0;
+const SKILL_NODE_SIZE: f32 = 30.0;
+const SKILL_NODE_SPACING: f32 = 40.0;
+
+/// Where `node` is drawn in the skill panel, in screen space
+fn skill_node_rect(node: &SkillNode) -> Rect {
+    Rect::new(
+        SKILL_PANEL_X + node.pos.0 as f32 * SKILL_NODE_SPACING,
+        SKILL_PANEL_Y + node.pos.1 as f32 * SKILL_NODE_SPACING,
+        SKILL_NODE_SIZE,
+        SKILL_NODE_SIZE,
+    )
+}
+
+/// K opens the skill panel. While it is open, clicking a node allocates
+/// it and R takes every skill back
+fn skill_panel_system(state: &mut GameState) -> SystemResult {
+    if state.input.keyboard.pressed.contains(&Keycode::K) {
+        state.skill_panel_open = !state.skill_panel_open;
+    }
+    if !state.skill_panel_open {
+        return Ok(());
+    }
+    let mut player = state.ecs.get::<&mut Player>(state.player)?;
+    let Player {
+        experience, skills, ..
+    } = &mut *player;
+    if state.input.keyboard.pressed.contains(&Keycode::R) {
+        skills.reset(experience);
+    }
+
+    let mouse = &mut state.input.mouse;
+    let clicked = SKILL_TREE
+        .iter()
+        .find(|node| skill_node_rect(node).contains(mouse.pos));
+    if let Some(node) = clicked {
+        if mouse.pressed.contains(&MouseButton::Left) {
+            // can't afford it or it is locked, the panel shows which
+            let _ = skills.allocate(experience, node.id);
+        }
+        // the click was for the panel, don't mine or swing with it
+        mouse.pressed.remove(&MouseButton::Left);
+        mouse.held.remove(&MouseButton::Left);
+    }
+    Ok(())
+}
+
+fn player_use_system(state: &mut GameState) -> SystemResult {
+    if is_dead(state) {
+        return Ok(());
+    }
+    if !state.input.mouse.pressed.contains(&MouseButton::Right) {
+        return Ok(());
+    }
+    let (player, inventory) = state
+        .ecs
+        .query_one_mut::<(&Player, &Inventory)>(state.player)?;
+    let slot = player.active_inventory_slot;
+    let Some(held) = inventory.get_item(slot).copied() else {
+        return Ok(());
+    };
+
+    match click_action(Some(&held), MouseButton::Right) {
+        ItemAction::Place(block) => place_held_block(state, slot, block),
+        ItemAction::Equip => {
+            let (inventory, equipment) =
+                state
+                    .ecs
+                    .query_one_mut::<(&mut Inventory, &mut Equipment)>(
+                        state.player,
+                    )?;
+            let Some(equip_slot) = equipment.slot_for(&held) else {
+                return Ok(());
+            };
+            // swap out whatever was worn there, if there is room for it
+            let mut transaction = inventory.transaction();
+            transaction.take(slot, held.amount);
+            if let Some(previous) = equipment.get(equip_slot) {
+                transaction.add(*previous);
+            }
+            if transaction.check().is_err() {
+                return Ok(());
+            }
+            equipment.equip(equip_slot, held)?;
+            transaction.commit()?;
+            Ok(())
+        }
+        ItemAction::Consume => {
+            let Some(effect) = held.potion_effect().copied() else {
+                return Ok(());
+            };
+            {
+                let mut inventory =
+                    state.ecs.get::<&mut Inventory>(state.player)?;
+                let mut transaction = inventory.transaction();
+                transaction.take(slot, 1);
+                transaction.commit()?;
+            }
+            apply_effect(&mut state.ecs, state.player, effect);
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Place one `block` from inventory `slot` where the mouse is, if it is
+/// in reach, empty, and nothing is standing there
+fn place_held_block(
+    state: &mut GameState,
+    slot: usize,
+    block: BlockName,
+) -> SystemResult {
+    let mouse_pos = state.input.mouse.get_global_pos(&state.camera);
+    let tile = mouse_pos.floor().as_ivec2();
+
+    let (pos, stats) = state
+        .ecs
+        .query_one_mut::<(&Position, &Stats)>(state.player)?;
+    let reach = Player::MINING_REACH * stats.get(StatName::Reach);
+    if mouse_pos.distance_squared(pos.0) > reach * reach {
+        return Ok(());
+    }
+    // items lying on the floor don't get in the way
+    let occupied = state
+        .ecs
+        .query_mut::<Without<(&Position, &Collider), &DroppedItem>>()
+        .into_iter()
+        .any(|(_, (pos, collider))| {
+            boxes_overlap(tile.as_vec2(), Vec2::ONE, pos.0, collider.size)
+        });
+    if occupied {
+        return Ok(());
+    }
+
+    let mut inventory = state.ecs.get::<&mut Inventory>(state.player)?;
+    let mut transaction = inventory.transaction();
+    transaction.take(slot, 1);
+    if transaction.check().is_ok()
+        && state.map.place_block(tile, block_from_name(block, tile))
+    {
+        transaction.commit()?;
+    }
+    Ok(())
+}
+
+const INVENTORY_X: f32 = 10.0;
+const INVENTORY_Y: f32 = 10.0;
+const INVENTORY_COLUMNS: usize = 10;
+const SLOT_SIZE: f32 = 30.0;
+const SLOT_MARGIN: f32 = 2.0;
+const ITEM_PADDING: f32 = 4.0;
+
+/// Where inventory slot `index` is drawn, in screen space
+fn inventory_slot_rect(index: usize) -> Rect {
+    let x = (index % INVENTORY_COLUMNS) as f32;
+    let y = (index / INVENTORY_COLUMNS) as f32;
+    Rect::new(
+        INVENTORY_X + x * (SLOT_SIZE + SLOT_MARGIN),
+        INVENTORY_Y + y * (SLOT_SIZE + SLOT_MARGIN),
+        SLOT_SIZE,
+        SLOT_SIZE,
+    )
+}
+
+/// `item`'s icon, inside a slot drawn at `slot`
+fn draw_item_icon(
+    canvas: &mut Canvas<Window>,
+    slot: Rect,
+    item: &Item,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let fill = Rect::new(
+        slot.x + SLOT_MARGIN,
+        slot.y + SLOT_MARGIN,
+        slot.w - 2.0 * SLOT_MARGIN,
+        slot.h - 2.0 * SLOT_MARGIN,
+    );
+    canvas.set_draw_color(item.color);
+    draw_rect_with_hole(
+        canvas,
+        fill,
+        Rect::new(
+            fill.x + ITEM_PADDING,
+            fill.y + ITEM_PADDING,
+            fill.w - 2.0 * ITEM_PADDING,
+            fill.h - 2.0 * ITEM_PADDING,
+        ),
+    )
+}
+
+/// Where the bottom of an inventory of `len` slots is drawn. The bars and
+/// panels under it move down as bags add rows
+fn inventory_bottom(len: usize) -> f32 {
+    INVENTORY_Y
+        + len.div_ceil(INVENTORY_COLUMNS) as f32 * (SLOT_SIZE + SLOT_MARGIN)
+}
+
+const STASH_PANEL_X: f32 = 10.0;
+const STASH_PANEL_OFFSET: f32 = 72.0; // under the status effects
+
+/// Where the `index`th shown stash entry is drawn, in screen space, under
+/// an inventory of `inventory_len` slots
+fn stash_slot_rect(index: usize, inventory_len: usize) -> Rect {
+    let x = (index % INVENTORY_COLUMNS) as f32;
+    let y = (index / INVENTORY_COLUMNS) as f32;
+    Rect::new(
+        STASH_PANEL_X + x * (SLOT_SIZE + SLOT_MARGIN),
+        inventory_bottom(inventory_len)
+            + STASH_PANEL_OFFSET
+            + y * (SLOT_SIZE + SLOT_MARGIN),
+        SLOT_SIZE,
+        SLOT_SIZE,
+    )
+}
+
+/// F opens the stash next to a stash block, Escape closes it. While it
+/// is open: typing searches, Tab changes the sort, clicking an inventory
+/// slot deposits every item like it, left clicking a stash entry takes a
+/// stack and right clicking takes one
+fn stash_panel_system(state: &mut GameState) -> SystemResult {
+    let (player, inventory, pos, collider) = state.ecs.query_one_mut::<(
+        &mut Player,
+        &mut Inventory,
+        &Position,
+        &Collider,
+    )>(state.player)?;
+    let in_reach = stash_in_reach(&state.map, pos.0, collider.size);
+    let keys = &state.input.keyboard;
+    // F would end up in the search once open, so Escape closes it
+    if keys.pressed.contains(&Keycode::F) && !state.stash_open {
+        state.stash_open = true;
+        return Ok(());
+    }
+    if keys.pressed.contains(&Keycode::Escape) {
+        state.stash_open = false;
+    }
+    state.stash_open &= in_reach;
+    if !state.stash_open {
+        state.stash_query.clear();
+        return Ok(());
+    }
+
+    state
+        .stash_query
+        .extend(keys.text.chars().filter(|c| c.is_alphanumeric()));
+    if keys.pressed.contains(&Keycode::Backspace) {
+        state.stash_query.pop();
+    }
+    if keys.pressed.contains(&Keycode::Tab) {
+        state.stash_sort = state.stash_sort.next();
+        player.stash.sort(state.stash_sort);
+    }
+    // the search box has the keyboard, so typing `q` or `r` doesn't throw
+    // the held item or reset the skills
+    state.input.keyboard.pressed.clear();
+
+    let mouse = &mut state.input.mouse;
+    let left = mouse.pressed.contains(&MouseButton::Left);
+    let right = mouse.pressed.contains(&MouseButton::Right);
+    let shown = player.stash.search(&state.stash_query);
+    let on_stash = (0..shown.len())
+        .find(|&i| stash_slot_rect(i, inventory.len()).contains(mouse.pos));
+    let on_inventory = (0..inventory.len())
+        .find(|&i| inventory_slot_rect(i).contains(mouse.pos));
+
+    if let Some(shown_index) = on_stash {
+        let index = shown[shown_index];
+        let stack = player.stash.get(index).map_or(0, |item| item.max_stack);
+        if left {
+            player.stash.withdraw(index, stack, inventory);
+        } else if right {
+            player.stash.withdraw(index, 1, inventory);
+        }
+    } else if let Some(slot) = on_inventory.filter(|_| left) {
+        if let Some(item) = inventory.get_item(slot).copied() {
+            player.stash.deposit_all(inventory, item.name);
+        }
+    } else {
+        return Ok(());
+    }
+    // the click was for the stash, don't mine or use things with it
+    for button in [MouseButton::Left, MouseButton::Right] {
+        mouse.pressed.remove(&button);
+        mouse.held.remove(&button);
+    }
+    Ok(())
+}
+
+/// Built in, so the game runs from any directory
+const FONT: &[u8] = include_bytes!("../assets/fonts/DejaVuSansMono.ttf");
+const FONT_SIZE: u16 = 10;
+
+const DEBUG_OVERLAY_X: f32 = 600.0;
+const DEBUG_OVERLAY_Y: f32 = 150.0; // under the skill panel
+const DEBUG_ROW_HEIGHT: f32 = 12.0;
+
+/// The on/off square of the `index`th system in the debug overlay
+fn debug_row_rect(index: usize) -> Rect {
+    Rect::new(
+        DEBUG_OVERLAY_X,
+        DEBUG_OVERLAY_Y + index as f32 * DEBUG_ROW_HEIGHT,
+        DEBUG_ROW_HEIGHT - 2.0,
+        DEBUG_ROW_HEIGHT - 2.0,
+    )
+}
+
+const EQUIPMENT_PANEL_X: f32 = 340.0; // right of the inventory grid
+const EQUIPMENT_PANEL_Y: f32 = 10.0;
+const EQUIPMENT_SLOT_SIZE: f32 = 30.0;
+const EQUIPMENT_SLOT_MARGIN: f32 = 2.0;
+
+/// Where `slot` is drawn in the equipment panel, in screen space
+fn equipment_slot_rect(slot: EquipmentSlot) -> Rect {
+    Rect::new(
+        EQUIPMENT_PANEL_X,
+        EQUIPMENT_PANEL_Y
+            + slot.index() as f32
+                * (EQUIPMENT_SLOT_SIZE + EQUIPMENT_SLOT_MARGIN),
+        EQUIPMENT_SLOT_SIZE,
+        EQUIPMENT_SLOT_SIZE,
+    )
+}
+
+/// Clicking a worn item takes it off, back into the inventory
+fn equipment_panel_system(state: &mut GameState) -> SystemResult {
+    let mouse = &mut state.input.mouse;
+    let clicked = EquipmentSlot::ALL
+        .into_iter()
+        .find(|&slot| equipment_slot_rect(slot).contains(mouse.pos));
+    let Some(slot) = clicked else {
+        return Ok(());
+    };
+    let pressed = mouse.pressed.contains(&MouseButton::Left);
+    // the click was for the panel, don't mine or swing with it
+    mouse.pressed.remove(&MouseButton::Left);
+    mouse.held.remove(&MouseButton::Left);
+    if !pressed || is_dead(state) {
+        return Ok(());
+    }
+
+    let (inventory, equipment) = state
+        .ecs
+        .query_one_mut::<(&mut Inventory, &mut Equipment)>(state.player)?;
+    let Some(&item) = equipment.get(slot) else {
+        return Ok(());
+    };
+    let mut transaction = inventory.transaction();
+    transaction.add(item);
+    // no room, keep wearing it
+    if transaction.commit().is_ok() {
+        equipment.unequip(slot);
+    }
+    Ok(())
+}
+
+fn player_throw_system(state: &mut GameState) -> SystemResult {
+    if is_dead(state) {
+        return Ok(());
+    }
+    if state.input.keyboard.pressed.contains(&Keycode::Q) {
+        throw_held_item(&mut state.ecs, state.player);
+    }
+    Ok(())
+}
+
+fn snapshot_positions_system(state: &mut GameState) -> SystemResult {
+    for (_, (pos, previous)) in
+        state.ecs.query_mut::<(&Position, &mut PreviousPosition)>()
+    {
+        previous.0 = pos.0;
+    }
+    Ok(())
+}
+
+fn heal_blocks_system(state: &mut GameState) -> SystemResult {
+    for block in &state.blocks {
+        // blocks naturally heal, but slowly
+        state.map.heal_block(block.pos, 0.1);
+    }
+    Ok(())
+}
+
+fn dropped_items_system(state: &mut GameState) -> SystemResult {
+    update_dropped_items(&mut state.ecs, state.player, TICK_RATE);
+    Ok(())
+}
+
+fn generate_chunks_system(state: &mut GameState) -> SystemResult {
+    state.map.generate_around_point(
+        state.camera.pos.x,
+        state.camera.pos.y,
+        state.camera.viewport_dims.x,
+        state.camera.viewport_dims.y,
+    );
+    Ok(())
+}
+
+fn camera_follow_system(state: &mut GameState) -> SystemResult {
+    let pos = state.ecs.get::<&Position>(state.player)?.0;
+    state.camera.center_around(pos);
+    Ok(())
+}
+
+/// Registers every built-in system, in its stage
+fn register_systems(scheduler: &mut Scheduler<GameState>) {
+    let systems = [
+        System::new(
+            "snapshot_positions",
+            Stage::Input,
+            snapshot_positions_system,
+        ),
+        System::new("sync_stats", Stage::Input, |state: &mut GameState| {
+            sync_stats_system(&mut state.ecs);
+            Ok(())
+        })
+        .before("player_movement")
+        .before("player_mining")
+        .before("player_attack"),
+        System::new(
+            "sync_inventory_size",
+            Stage::Input,
+            |state: &mut GameState| {
+                sync_inventory_size_system(&mut state.ecs);
+                Ok(())
+            },
+        )
+        .before("player_use")
+        .before("player_throw")
+        .before("stash_panel"),
+        System::new("hotbar", Stage::Input, hotbar_system)
+            .before("player_mining")
+            .before("player_attack")
+            .before("player_use")
+            .before("player_throw")
+            .before("sync_stats"),
+        System::new("stash_panel", Stage::Input, stash_panel_system)
+            .before("hotbar")
+            .before("skill_panel")
+            .before("player_ability")
+            .before("player_throw")
+            .before("player_mining")
+            .before("player_attack")
+            .before("player_use")
+            .before("player_movement"),
+        System::new("skill_panel", Stage::Input, skill_panel_system)
+            .before("player_mining")
+            .before("player_attack"),
+        System::new("equipment_panel", Stage::Input, equipment_panel_system)
+            .before("player_mining")
+            .before("player_attack"),
+        System::new("player_ability", Stage::Input, player_ability_system)
+            .after("sync_stats")
+            .before("player_movement"),
+        System::new("player_movement", Stage::Input, player_movement_system),
+        System::new("player_mining", Stage::Input, player_mining_system),
+        System::new("player_attack", Stage::Input, player_attack_system),
+        System::new("player_use", Stage::Input, player_use_system),
+        System::new("player_throw", Stage::Input, player_throw_system)
+            .after("player_mining"),
+        System::new("active_chunks", Stage::PrePhysics, active_chunks_system),
+        System::new("dropped_items", Stage::PrePhysics, dropped_items_system),
+        System::new(
+            "projectile_flight",
+            Stage::PrePhysics,
+            |state: &mut GameState| {
+                projectile_flight_system(&mut state.ecs, TICK_RATE);
+                Ok(())
+            },
+        ),
+        System::new(
+            "projectile_collision",
+            Stage::PostPhysics,
+            |state: &mut GameState| {
+                projectile_collision_system(&mut state.ecs);
+                Ok(())
+            },
+        )
+        .before("damage"),
+        System::new(
+            "enemy_sleep",
+            Stage::PrePhysics,
+            |state: &mut GameState| {
+                enemy_sleep_system(&mut state.ecs, &state.map);
+                Ok(())
+            },
+        )
+        .after("active_chunks"),
+        System::new("enemy_ai", Stage::PrePhysics, |state: &mut GameState| {
+            enemy_ai_system(
+                &mut state.ecs,
+                &mut state.map,
+                state.player,
+                TICK_RATE,
+            );
+            Ok(())
+        })
+        .after("enemy_sleep")
+        .after("invalidate_paths"),
+        System::new(
+            "invalidate_paths",
+            Stage::PrePhysics,
+            |state: &mut GameState| {
+                invalidate_paths_system(&mut state.ecs, &mut state.map);
+                Ok(())
+            },
+        ),
+        System::new("gravity", Stage::Physics, |state: &mut GameState| {
+            gravity_system(&mut state.ecs, TICK_RATE);
+            Ok(())
+        })
+        .before("movement"),
+        System::new("movement", Stage::Physics, |state: &mut GameState| {
+            movement_system(&mut state.ecs, &state.map, TICK_RATE);
+            Ok(())
+        }),
+        System::new("hazards", Stage::PostPhysics, |state: &mut GameState| {
+            hazard_system(&mut state.ecs, &state.map);
+            Ok(())
+        })
+        .before("damage"),
+        System::new(
+            "status_effects",
+            Stage::PostPhysics,
+            |state: &mut GameState| {
+                status_effect_system(&mut state.ecs, TICK_RATE);
+                Ok(())
+            },
+        )
+        .before("death")
+        .before("player_death"),
+        System::new(
+            "fall_damage",
+            Stage::PostPhysics,
+            |state: &mut GameState| {
+                fall_damage_system(&mut state.ecs, &state.map);
+                Ok(())
+            },
+        )
+        .before("damage"),
+        System::new("damage", Stage::PostPhysics, |state: &mut GameState| {
+            damage_system(&mut state.ecs);
+            Ok(())
+        }),
+        System::new(
+            "enemy_loot",
+            Stage::PostPhysics,
+            |state: &mut GameState| {
+                enemy_loot_system(&mut state.ecs, &mut state.map);
+                Ok(())
+            },
+        )
+        .after("damage")
+        .before("death"),
+        System::new("enemy_xp", Stage::PostPhysics, |state: &mut GameState| {
+            enemy_xp_system(&mut state.ecs);
+            Ok(())
+        })
+        .after("damage")
+        .before("death"),
+        System::new("death", Stage::PostPhysics, |state: &mut GameState| {
+            death_system(&mut state.ecs);
+            Ok(())
+        })
+        .after("damage"),
+        System::new(
+            "player_death",
+            Stage::PostPhysics,
+            |state: &mut GameState| {
+                player_death_system(&mut state.ecs);
+                Ok(())
+            },
+        )
+        .after("damage"),
+        System::new("respawn", Stage::PostPhysics, |state: &mut GameState| {
+            respawn_system(&mut state.ecs);
+            Ok(())
+        })
+        .after("player_death"),
+        System::new("generate_chunks", Stage::World, generate_chunks_system),
+        System::new("heal_blocks", Stage::World, heal_blocks_system),
+        System::new("enemy_spawn", Stage::World, |state: &mut GameState| {
+            enemy_spawn_system(&mut state.ecs, &mut state.map, state.player);
+            Ok(())
+        }),
+        System::new("camera_follow", Stage::RenderPrep, camera_follow_system),
+    ];
+    for system in systems {
+        // names are all distinct, this can't fail
+        scheduler.add_system(system).unwrap();
+    }
 }
 
 impl Game {
@@ -47,30 +985,54 @@ impl Game {
         let window = canvas.window();
         let event_pump = sdl_context.event_pump().unwrap();
 
+        let mut ecs = HecsWorld::new();
+        let player = spawn_player(&mut ecs, Vec2::ZERO);
+
+        let mut scheduler = Scheduler::new();
+        register_systems(&mut scheduler);
+
+        // kept for the whole run, so the font can borrow it forever
+        let ttf: &'static Sdl2TtfContext =
+            Box::leak(Box::new(sdl2::ttf::init().unwrap()));
+        let font = ttf
+            .load_font_from_rwops(RWops::from_bytes(FONT).unwrap(), FONT_SIZE)
+            .unwrap();
+
         Self {
-            map: World::new(seed),
-            camera: Camera::new(
-                Vec2::new(0.0, 40.0),
-                camera_dims,
-                window_dims,
-                0.0,
-            ),
+            state: GameState {
+                map: World::new(seed),
+                camera: Camera::new(
+                    Vec2::new(0.0, 40.0),
+                    camera_dims,
+                    window_dims,
+                    0.0,
+                ),
+                ecs,
+                input: Input::new(),
+                player,
+                tick_count: 0,
+                blocks: Vec::new(),
+                skill_panel_open: false,
+                stash_open: false,
+                stash_query: String::new(),
+                stash_sort: StashSort::default(),
+            },
+            scheduler,
+            debug_open: false,
+            font,
             sdl_context,
             video_subsystem,
             window: window.clone(),
             canvas,
             event_pump,
-            ecs: HecsWorld::new(),
-            input: Input::new(),
-            player: Player::default(),
-            tick_count: 0,
         }
     }
 
+    /// Pull in this frame's events. Presses and releases stay around
+    /// until a tick has seen them, even if this frame runs no ticks
     pub fn manage_input(&mut self) -> bool {
-        self.input.clear_transient();
         for event in self.event_pump.poll_iter() {
-            if !self.input.update(&event, &self.camera) {
+            if !self.state.input.update(&event, &self.state.camera) {
                 return false;
             }
         }
@@ -78,153 +1040,498 @@ impl Game {
     }
 
     pub fn tick(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        self.tick_count += 1;
-        self.map.update_active_chunks(
-            self.camera.pos.x,
-            self.camera.pos.y,
-            self.camera.viewport_dims.x as i32,
-            self.camera.viewport_dims.y as i32,
-        );
-        let blocks: Vec<Block> = self
-            .map
-            .get_active_chunks()
-            .iter()
-            .flat_map(|c| c.flatten())
-            .collect();
-        if self.input.keyboard.held.contains(&Keycode::SPACE) {
-            self.player.try_jump(&blocks);
+        self.state.tick_count += 1;
+        if self.state.input.keyboard.pressed.contains(&Keycode::F3) {
+            self.debug_open = !self.debug_open;
         }
-        if self.input.keyboard.held.contains(&Keycode::Left)
-            || self.input.keyboard.held.contains(&Keycode::A)
-        {
-            self.player.try_move(Direction::Left, FPS);
-        } else if self.input.keyboard.held.contains(&Keycode::Right)
-            || self.input.keyboard.held.contains(&Keycode::D)
-        {
-            self.player.try_move(Direction::Right, FPS);
-        } else {
-            self.player.apply_friction(FPS);
-        }
-
-        if self.input.mouse.held.contains(&MouseButton::Left) {
-            let pos = self.input.mouse.get_global_pos(&self.camera);
-
-            if pos.distance_squared(self.player.pos) <= 5.0 * 5.0 {
-                self.player.hit_block(
-                    IVec2::new(pos.x.floor() as i32, pos.y.floor() as i32),
-                    &mut self.map,
-                    self.tick_count,
-                );
-                // self.map.hit_block(
-                //     IVec2::new(pos.x.floor() as i32, pos.y.floor() as i32),
-                //     &mut self.player,
-                // );
-            }
+        if self.debug_open {
+            self.debug_overlay_input();
         }
+        self.scheduler.run(&mut self.state)
+    }
 
-        self.player.apply_gravity(FPS);
-        self.player.move_step(&blocks, FPS);
-
-        self.map.generate_around_point(
-            self.camera.pos.x,
-            self.camera.pos.y,
-            self.camera.viewport_dims.x,
-            self.camera.viewport_dims.y,
-        );
-
-        self.camera.center_around(self.player.pos);
-        Ok(())
+    /// Clicking a system's square in the debug overlay turns it on or off
+    fn debug_overlay_input(&mut self) {
+        let mouse = &mut self.state.input.mouse;
+        if !mouse.pressed.contains(&MouseButton::Left) {
+            return;
+        }
+        let Ok(systems) = self.scheduler.systems() else {
+            return;
+        };
+        let Some(name) = (0..systems.len())
+            .find(|&i| debug_row_rect(i).contains(mouse.pos))
+            .map(|i| systems[i].name)
+        else {
+            return;
+        };
+        self.scheduler.toggle(name);
+        mouse.pressed.remove(&MouseButton::Left);
+        mouse.held.remove(&MouseButton::Left);
     }
 
     /// Runs once after initialisation
-    pub fn on_start(&mut self) {}
+    pub fn on_start(&mut self) {
+        let loaded = load_player(
+            &mut self.state.ecs,
+            self.state.player,
+            Path::new(SAVE_PATH),
+        )
+        .unwrap_or_else(|e| {
+            eprintln!("Error when loading the player: {}", e);
+            false
+        });
+        // the kit is only for players without a saved inventory, or it
+        // could be stashed and copied by restarting
+        if loaded {
+            return;
+        }
+        let Ok(mut inventory) =
+            self.state.ecs.get::<&mut Inventory>(self.state.player)
+        else {
+            return;
+        };
+        // starter tools
+        for item in [
+            item_wooden_pickaxe(1),
+            item_wooden_shovel(1),
+            item_wooden_axe(1),
+            item_wooden_sword(1),
+            item_wooden_bow(1),
+            item_magic_wand(1),
+            item_stone_spear(1),
+            item_venom_dagger(1),
+            item_regeneration_potion(3),
+            item_speed_potion(3),
+            item_haste_potion(3),
+            item_feather_fall_potion(3),
+            item_leather_cap(1),
+            item_leather_tunic(1),
+            item_leather_leggings(1),
+            item_runners_ring(1),
+            item_miners_charm(1),
+            item_stash(1),
+            item_backpack(1),
+        ] {
+            inventory.add_item(item);
+        }
+    }
 
-    pub fn render(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+    /// `alpha` is how far we are between the last tick and the next one,
+    /// used to smooth out movement when rendering faster than ticking
+    pub fn render(
+        &mut self,
+        alpha: f32,
+    ) -> Result<(), Box<dyn std::error::Error>> {
         self.canvas.set_draw_color((0, 0, 0));
         self.canvas.clear();
 
-        let blocks: Vec<Block> = self
-            .map
-            .get_active_chunks()
+        // follow where the player is drawn, not where it was last tick
+        let mut camera = self.state.camera;
+        if let Some(pos) =
+            interpolated_position(&self.state.ecs, self.state.player, alpha)
+        {
+            camera.center_around(pos);
+        }
+
+        for block in &self.state.blocks {
+            block.render(&mut self.canvas, &camera)?;
+        }
+        render_system(&self.state.ecs, &mut self.canvas, &camera, alpha)?;
+
+        self.render_health_bar()?;
+        self.render_xp_bar()?;
+        self.render_status_effects()?;
+        self.render_equipment_panel()?;
+        if self.state.skill_panel_open {
+            self.render_skill_panel()?;
+        }
+        if self.state.stash_open {
+            self.render_stash_panel()?;
+        }
+        if self.debug_open {
+            self.render_debug_overlay()?;
+        }
+
+        let items = self
+            .state
+            .ecs
+            .get::<&Inventory>(self.state.player)?
+            .get_items()
+            .to_vec();
+        let active_slot = self
+            .state
+            .ecs
+            .get::<&Player>(self.state.player)?
+            .active_inventory_slot;
+        for (index, item) in items.iter().enumerate() {
+            let rect = inventory_slot_rect(index);
+            // the first row is the hotbar, the held slot stands out
+            self.canvas.set_draw_color(if index == active_slot {
+                (255, 215, 0)
+            } else if index < Player::HOTBAR_SIZE {
+                (230, 230, 230)
+            } else {
+                (255, 255, 255)
+            });
+            rect.draw(&mut self.canvas)?;
+            if let Some(item) = item {
+                draw_item_icon(&mut self.canvas, rect, item)?;
+            }
+        }
+        self.canvas.present();
+        Ok(())
+    }
+
+    /// A row per system in run order: a square that is green while it is
+    /// enabled, then its name and how long its last run took, over a bar
+    /// of that time
+    fn render_debug_overlay(
+        &mut self,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        const TEXT_GAP: f32 = 3.0;
+        const BAR_MAX_WIDTH: f32 = 170.0;
+        const PIXELS_PER_MS: f32 = 170.0; // a full bar is a millisecond
+
+        let rows: Vec<(&str, bool, f32)> = self
+            .scheduler
+            .systems()?
             .iter()
-            .flat_map(|c| c.flatten())
+            .map(|s| {
+                let ms = s.last_duration.as_secs_f32() * 1000.0;
+                (s.name, s.enabled, ms)
+            })
             .collect();
+        for (index, (name, enabled, ms)) in rows.into_iter().enumerate() {
+            let rect = debug_row_rect(index);
+            self.canvas.set_draw_color(if enabled {
+                (80, 200, 80)
+            } else {
+                (200, 60, 60)
+            });
+            rect.draw(&mut self.canvas)?;
+
+            let text_x = rect.max_x() + TEXT_GAP;
+            self.canvas.set_draw_color((60, 60, 90));
+            Rect::new(
+                text_x,
+                rect.y,
+                (ms * PIXELS_PER_MS).min(BAR_MAX_WIDTH),
+                rect.h,
+            )
+            .draw(&mut self.canvas)?;
+            draw_text(
+                &mut self.canvas,
+                &self.font,
+                &format!("{name:<20} {ms:>5.2}ms"),
+                Vec2::new(text_x, rect.y - 1.0),
+                if enabled {
+                    (230, 230, 230)
+                } else {
+                    (150, 150, 150)
+                },
+            )?;
+        }
+        Ok(())
+    }
+
+    fn inventory_len(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        Ok(self.state.ecs.get::<&Inventory>(self.state.player)?.len())
+    }
+
+    fn render_health_bar(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        const HEALTH_BAR_X: f32 = 10.0;
+        const HEALTH_BAR_WIDTH: f32 = 200.0;
+        const HEALTH_BAR_HEIGHT: f32 = 12.0;
+        let health_bar_y = inventory_bottom(self.inventory_len()?) + 12.0;
+
+        let health = *self.state.ecs.get::<&Health>(self.state.player)?;
+        let percent = if health.max == 0.0 {
+            0.0
+        } else {
+            (health.current / health.max).clamp(0.0, 1.0)
+        };
 
-        for block in blocks {
-            // blocks naturally heal, but slowly
-            self.map.heal_block(block.pos, 0.1);
-            block.render(&mut self.canvas, &self.camera)?;
+        self.canvas.set_draw_color((60, 0, 0));
+        Rect::new(
+            HEALTH_BAR_X,
+            health_bar_y,
+            HEALTH_BAR_WIDTH,
+            HEALTH_BAR_HEIGHT,
+        )
+        .draw(&mut self.canvas)?;
+        // flash white while invulnerable
+        self.canvas.set_draw_color(if health.is_invulnerable() {
+            (255, 255, 255)
+        } else {
+            (200, 20, 20)
+        });
+        Rect::new(
+            HEALTH_BAR_X,
+            health_bar_y,
+            HEALTH_BAR_WIDTH * percent,
+            HEALTH_BAR_HEIGHT,
+        )
+        .draw(&mut self.canvas)?;
+        Ok(())
+    }
+
+    /// A thin bar under the health bar, with a notch per level
+    fn render_xp_bar(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        const XP_BAR_X: f32 = 10.0;
+        const XP_BAR_WIDTH: f32 = 200.0;
+        const XP_BAR_HEIGHT: f32 = 4.0;
+        let xp_bar_y = inventory_bottom(self.inventory_len()?) + 25.0;
+
+        let experience =
+            self.state.ecs.get::<&Player>(self.state.player)?.experience;
+
+        self.canvas.set_draw_color((20, 40, 20));
+        Rect::new(XP_BAR_X, xp_bar_y, XP_BAR_WIDTH, XP_BAR_HEIGHT)
+            .draw(&mut self.canvas)?;
+        self.canvas.set_draw_color((90, 220, 60));
+        Rect::new(
+            XP_BAR_X,
+            xp_bar_y,
+            XP_BAR_WIDTH * experience.progress().clamp(0.0, 1.0),
+            XP_BAR_HEIGHT,
+        )
+        .draw(&mut self.canvas)?;
+        // one notch per level, to the right of the bar
+        self.canvas.set_draw_color((255, 255, 255));
+        for level in 0..experience.level.min(20) {
+            Rect::new(
+                XP_BAR_X + XP_BAR_WIDTH + 4.0 + level as f32 * 4.0,
+                xp_bar_y,
+                2.0,
+                XP_BAR_HEIGHT,
+            )
+            .draw(&mut self.canvas)?;
         }
-        self.player.render(&mut self.canvas, &self.camera)?;
+        Ok(())
+    }
 
-        const INVENTORY_ITEM_SIZE: usize = 30;
-        const INVENTORY_ITEM_MARGIN: usize = 2;
-        const INVENTORY_ITEM_PADDING: usize = 4;
-        const INVENTORY_MARGIN: usize = 10;
+    /// One slot per piece of equipment, next to the inventory grid
+    fn render_equipment_panel(
+        &mut self,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let equipment = *self.state.ecs.get::<&Equipment>(self.state.player)?;
+        for (slot, item) in equipment.iter() {
+            let rect = equipment_slot_rect(slot);
+            // darker than inventory slots, so the two read apart
+            self.canvas.set_draw_color((170, 170, 190));
+            rect.draw(&mut self.canvas)?;
 
-        for (index, item) in
-            self.player.inventory.get_items().iter().enumerate()
-        {
-            // 10xN grid
-            let x = index % 10;
-            let y = index / 10;
+            if let Some(item) = item {
+                draw_item_icon(&mut self.canvas, rect, item)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Every stash entry matching the search. The bar under each fills
+    /// up to one stack, and turns gold past that
+    fn render_stash_panel(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        const AMOUNT_HEIGHT: f32 = 3.0;
+
+        let player = self.state.ecs.get::<&Player>(self.state.player)?;
+        let shown: Vec<Item> = player
+            .stash
+            .search(&self.state.stash_query)
+            .into_iter()
+            .filter_map(|index| player.stash.get(index).copied())
+            .collect();
+        drop(player);
+        let inventory_len = self.inventory_len()?;
+
+        for (index, item) in shown.iter().enumerate() {
+            let rect = stash_slot_rect(index, inventory_len);
+            self.canvas.set_draw_color((200, 180, 220));
+            rect.draw(&mut self.canvas)?;
+            draw_item_icon(&mut self.canvas, rect, item)?;
+
+            let stacks = item.amount as f32 / item.max_stack.max(1) as f32;
+            self.canvas.set_draw_color(if stacks > 1.0 {
+                (255, 215, 0)
+            } else {
+                (255, 255, 255)
+            });
+            Rect::new(
+                rect.x,
+                rect.max_y() - AMOUNT_HEIGHT,
+                rect.w * stacks.min(1.0),
+                AMOUNT_HEIGHT,
+            )
+            .draw(&mut self.canvas)?;
+        }
+        Ok(())
+    }
 
-            let x = x * (INVENTORY_ITEM_SIZE + INVENTORY_ITEM_MARGIN)
-                + INVENTORY_MARGIN;
-            let y = y * (INVENTORY_ITEM_SIZE + INVENTORY_ITEM_MARGIN)
-                + INVENTORY_MARGIN;
+    /// Every node of the skill tree: bright if allocated, dim if it can be
+    /// allocated now, dark if not. Notches under the panel are the
+    /// unspent skill points
+    fn render_skill_panel(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let player = self.state.ecs.get::<&Player>(self.state.player)?;
+        let points = player.experience.skill_points;
+        let skills = player.skills.clone();
+        drop(player);
 
+        for node in SKILL_TREE {
+            // lines to what it needs, under the nodes
+            self.canvas.set_draw_color((80, 80, 80));
+            for &required in node.requires {
+                let Some(required) = skill_from_id(required) else {
+                    continue;
+                };
+                let from = skill_node_rect(required);
+                let to = skill_node_rect(node);
+                let a = Vec2::new(from.x, from.y) + SKILL_NODE_SIZE / 2.0;
+                let b = Vec2::new(to.x, to.y) + SKILL_NODE_SIZE / 2.0;
+                Rect::new(a.x.min(b.x), a.y, (a.x - b.x).abs() + 2.0, 2.0)
+                    .draw(&mut self.canvas)?;
+                Rect::new(b.x, a.y.min(b.y), 2.0, (a.y - b.y).abs())
+                    .draw(&mut self.canvas)?;
+            }
+        }
+        for node in SKILL_TREE {
+            let (r, g, b) = node.color;
+            self.canvas.set_draw_color(if skills.has(node.id) {
+                (r, g, b)
+            } else if skills.check(node.id, points).is_ok() {
+                (r / 2, g / 2, b / 2)
+            } else {
+                (40, 40, 40)
+            });
+            let rect = skill_node_rect(node);
+            rect.draw(&mut self.canvas)?;
+            // one notch per point it costs
             self.canvas.set_draw_color((255, 255, 255));
+            for cost in 0..node.cost {
+                Rect::new(
+                    rect.x + 2.0 + cost as f32 * 4.0,
+                    rect.y + 2.0,
+                    2.0,
+                    2.0,
+                )
+                .draw(&mut self.canvas)?;
+            }
+        }
+
+        let bottom = SKILL_TREE
+            .iter()
+            .map(|node| skill_node_rect(node).max_y())
+            .fold(SKILL_PANEL_Y, f32::max);
+        self.canvas.set_draw_color((255, 215, 0));
+        for point in 0..points.min(20) {
             Rect::new(
-                x as f32,
-                y as f32,
-                INVENTORY_ITEM_SIZE as f32,
-                INVENTORY_ITEM_SIZE as f32,
+                SKILL_PANEL_X + point as f32 * 6.0,
+                bottom + 6.0,
+                4.0,
+                4.0,
             )
             .draw(&mut self.canvas)?;
+        }
+        Ok(())
+    }
 
-            if let Some(item) = item {
-                let fill_x = x + INVENTORY_ITEM_MARGIN;
-                let fill_y = y + INVENTORY_ITEM_MARGIN;
-                let fill_size =
-                    INVENTORY_ITEM_SIZE - 2 * (INVENTORY_ITEM_MARGIN);
-                self.canvas.set_draw_color(item.color);
-                draw_rect_with_hole(
-                    &mut self.canvas,
-                    Rect::new(
-                        fill_x as f32,
-                        fill_y as f32,
-                        fill_size as f32,
-                        fill_size as f32,
-                    ),
-                    Rect::new(
-                        fill_x as f32 + INVENTORY_ITEM_PADDING as f32,
-                        fill_y as f32 + INVENTORY_ITEM_PADDING as f32,
-                        fill_size as f32 - 2.0 * INVENTORY_ITEM_PADDING as f32,
-                        fill_size as f32 - 2.0 * INVENTORY_ITEM_PADDING as f32,
-                    ),
-                )?;
+    /// One icon per active effect, under the health bar. The bar below
+    /// each icon drains as the effect runs out
+    fn render_status_effects(
+        &mut self,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        const EFFECTS_X: f32 = 10.0;
+        const ICON_SIZE: f32 = 20.0;
+        const ICON_MARGIN: f32 = 4.0;
+        const TIMER_HEIGHT: f32 = 3.0;
+        let effects_y = inventory_bottom(self.inventory_len()?) + 32.0;
+
+        let effects = self
+            .state
+            .ecs
+            .get::<&StatusEffects>(self.state.player)?
+            .0
+            .clone();
+        for (index, effect) in effects.iter().enumerate() {
+            let x = EFFECTS_X + index as f32 * (ICON_SIZE + ICON_MARGIN);
+
+            self.canvas.set_draw_color(effect.color);
+            Rect::new(x, effects_y, ICON_SIZE, ICON_SIZE)
+                .draw(&mut self.canvas)?;
+            // one notch per level
+            self.canvas.set_draw_color((255, 255, 255));
+            for level in 0..effect.level.min(5) {
+                Rect::new(
+                    x + 2.0 + level as f32 * 4.0,
+                    effects_y + 2.0,
+                    2.0,
+                    2.0,
+                )
+                .draw(&mut self.canvas)?;
             }
+
+            self.canvas.set_draw_color((40, 40, 40));
+            Rect::new(x, effects_y + ICON_SIZE + 1.0, ICON_SIZE, TIMER_HEIGHT)
+                .draw(&mut self.canvas)?;
+            self.canvas.set_draw_color((255, 255, 255));
+            Rect::new(
+                x,
+                effects_y + ICON_SIZE + 1.0,
+                ICON_SIZE * effect.remaining_percent(),
+                TIMER_HEIGHT,
+            )
+            .draw(&mut self.canvas)?;
         }
-        self.canvas.present();
         Ok(())
     }
 
     pub fn run(&mut self) {
         self.on_start();
+        let mut last_frame = Instant::now();
+        let mut accumulator = Duration::ZERO;
+
         while self.manage_input() {
-            match self.tick() {
-                Ok(_) => {}
-                Err(e) => {
-                    eprintln!("Error when ticking: {}", e);
+            let now = Instant::now();
+            accumulator += (now - last_frame).min(MAX_FRAME_TIME);
+            last_frame = now;
+
+            let mut ticks = 0;
+            while accumulator >= TICK_DURATION {
+                if ticks == MAX_TICKS_PER_FRAME {
+                    // too far behind, give up on the backlog
+                    accumulator = Duration::ZERO;
+                    break;
+                }
+                match self.tick() {
+                    Ok(_) => {}
+                    Err(e) => {
+                        eprintln!("Error when ticking: {}", e);
+                    }
                 }
+                // presses only count for the tick that saw them
+                self.state.input.clear_transient();
+                accumulator -= TICK_DURATION;
+                ticks += 1;
             }
-            match self.render() {
+
+            let alpha = accumulator.as_secs_f32() / TICK_DURATION.as_secs_f32();
+            match self.render(alpha) {
                 Ok(_) => {}
                 Err(e) => {
                     eprintln!("Error when rendering: {}", e);
                 }
             }
         }
+        self.on_quit();
+    }
+
+    /// Runs once when the window is closed
+    pub fn on_quit(&mut self) {
+        if let Err(e) = save_player(
+            &self.state.ecs,
+            self.state.player,
+            Path::new(SAVE_PATH),
+        ) {
+            eprintln!("Error when saving the player: {}", e);
+        }
     }
 }
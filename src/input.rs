@@ -8,6 +8,7 @@ pub struct KeyboardInput {
     pub held: HashSet<Keycode>,
     pub released: HashSet<Keycode>,
     pub pressed: HashSet<Keycode>,
+    pub text: String, // typed this tick, for text boxes
 }
 
 #[derive(Clone, Debug)]
@@ -16,6 +17,7 @@ pub struct MouseInput {
     pub released: HashSet<MouseButton>,
     pub pressed: HashSet<MouseButton>,
     pub pos: Vec2,
+    pub wheel: i32, // notches scrolled this tick, positive is away from you
     world_pos: Vec2,
 }
 
@@ -38,6 +40,7 @@ impl KeyboardInput {
             held: HashSet::new(),
             released: HashSet::new(),
             pressed: HashSet::new(),
+            text: String::new(),
         }
     }
 }
@@ -56,6 +59,7 @@ impl MouseInput {
             released: HashSet::new(),
             pressed: HashSet::new(),
             pos: Vec2::ZERO,
+            wheel: 0,
             world_pos: Vec2::ZERO,
         }
     }
@@ -84,9 +88,11 @@ impl Input {
     pub fn clear_transient(&mut self) {
         self.keyboard.pressed.clear();
         self.keyboard.released.clear();
+        self.keyboard.text.clear();
 
         self.mouse.pressed.clear();
         self.mouse.released.clear();
+        self.mouse.wheel = 0;
     }
 
     /// Update input based on a single SDL2 event
@@ -111,6 +117,10 @@ impl Input {
                 self.keyboard.held.remove(key);
             }
 
+            Event::TextInput { text, .. } => {
+                self.keyboard.text += text;
+            }
+
             // Mouse button
             Event::MouseButtonDown { mouse_btn, .. } => {
                 self.mouse.pressed.insert(*mouse_btn);
@@ -121,6 +131,10 @@ impl Input {
                 self.mouse.held.remove(mouse_btn);
             }
 
+            Event::MouseWheel { y, .. } => {
+                self.mouse.wheel += *y;
+            }
+
             // Mouse movement
             Event::MouseMotion { x, y, .. } => {
                 self.mouse.pos = Vec2::new(*x as f32, *y as f32);
@@ -1,4 +1,8 @@
-use crate::{item::Item, utils::can_stack};
+use crate::{
+    item::{Item, ItemName, ItemType},
+    utils::can_stack,
+};
+use std::fmt;
 
 fn add_to_slot(slot: &mut Item, item: &mut Item) {
     let space = slot.max_stack - slot.amount;
@@ -6,17 +10,107 @@ fn add_to_slot(slot: &mut Item, item: &mut Item) {
     slot.amount += to_add;
     item.amount -= to_add;
 }
-#[derive(Clone, Copy, Debug)]
-pub struct Inventory<const N: usize> {
-    slots: [Option<Item>; N],
+
+/// What a slot will hold
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum SlotFilter {
+    #[default]
+    Any,
+    Type(ItemType), // e.g. materials only, for ore
+    Only(ItemName), // e.g. one kind of ammo
+}
+
+impl SlotFilter {
+    #[must_use]
+    pub fn accepts(&self, item: &Item) -> bool {
+        match self {
+            SlotFilter::Any => true,
+            SlotFilter::Type(item_type) => item.item_type == *item_type,
+            SlotFilter::Only(name) => item.name == *name,
+        }
+    }
 }
 
-impl<const N: usize> Inventory<N> {
-    pub fn new() -> Self {
-        Self { slots: [None; N] }
+/// Slots of items. How many can change while the game runs, e.g. when a
+/// bag is equipped, and each slot can be limited to some items
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Inventory {
+    slots: Vec<Option<Item>>,
+    filters: Vec<SlotFilter>, // one per slot
+}
+
+impl Inventory {
+    #[must_use]
+    pub fn new(size: usize) -> Self {
+        Self {
+            slots: vec![None; size],
+            filters: vec![SlotFilter::Any; size],
+        }
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.slots.len()
     }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Grow or shrink to `size` slots. New slots take any item. Items in
+    /// removed slots are moved into the remaining ones, and whatever
+    /// doesn't fit is returned
+    pub fn resize(&mut self, size: usize) -> Vec<Item> {
+        let removed: Vec<Item> = self
+            .slots
+            .drain(size.min(self.slots.len())..)
+            .flatten()
+            .collect();
+        self.slots.resize(size, None);
+        self.filters.resize(size, SlotFilter::Any);
+        removed
+            .into_iter()
+            .filter_map(|mut item| {
+                item.amount = self.add_item(item);
+                (item.amount > 0).then_some(item)
+            })
+            .collect()
+    }
+
+    #[must_use]
+    pub fn filter(&self, index: usize) -> Option<SlotFilter> {
+        self.filters.get(index).copied()
+    }
+
+    /// Limit slot `index` to `filter`. Returns false if it is out of
+    /// range or holds something the filter doesn't accept
+    pub fn set_filter(&mut self, index: usize, filter: SlotFilter) -> bool {
+        match self.slots.get(index) {
+            Some(Some(item)) if !filter.accepts(item) => false,
+            Some(_) => {
+                self.filters[index] = filter;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Can slot `index` hold `item` at all, ignoring what is in it
+    #[must_use]
+    pub fn accepts(&self, index: usize, item: &Item) -> bool {
+        self.filters
+            .get(index)
+            .is_some_and(|filter| filter.accepts(item))
+    }
+
+    /// Add as much of `item` as fits, returning how many didn't.
+    /// Use a `Transaction` when it has to be all or nothing
     pub fn add_item(&mut self, mut item: Item) -> usize {
-        for slot in &mut self.slots {
+        for (slot, filter) in self.slots.iter_mut().zip(&self.filters) {
+            if !filter.accepts(&item) {
+                continue;
+            }
             match slot {
                 Some(s) if can_stack(s, &item) => {
                     add_to_slot(s, &mut item);
@@ -39,7 +133,12 @@ impl<const N: usize> Inventory<N> {
         item.amount
     }
 
+    /// Remove `item.amount` of `item`, from the last slots first. If
+    /// there aren't that many nothing is removed and this returns false
     pub fn remove_item(&mut self, mut item: Item) -> bool {
+        if self.count(item.name) < item.amount {
+            return false;
+        }
         for slot in self.slots.iter_mut().rev() {
             match slot {
                 Some(s) if s.name == item.name => {
@@ -59,12 +158,599 @@ impl<const N: usize> Inventory<N> {
         item.amount == 0
     }
 
-    pub fn get_items(&self) -> [Option<Item>; N] {
+    /// How many of `name` there are, over every slot
+    #[must_use]
+    pub fn count(&self, name: ItemName) -> usize {
+        self.slots
+            .iter()
+            .flatten()
+            .filter(|item| item.name == name)
+            .map(|item| item.amount)
+            .sum()
+    }
+
+    /// Every slot holding `name`, first to last
+    pub fn find(&self, name: ItemName) -> impl Iterator<Item = usize> + '_ {
         self.slots
+            .iter()
+            .enumerate()
+            .filter(move |(_, slot)| slot.is_some_and(|item| item.name == name))
+            .map(|(index, _)| index)
+    }
+
+    #[must_use]
+    pub fn first_free(&self) -> Option<usize> {
+        self.slots.iter().position(Option::is_none)
+    }
+
+    /// The first empty slot that will take `item`
+    #[must_use]
+    pub fn first_free_for(&self, item: &Item) -> Option<usize> {
+        (0..self.len()).find(|&index| {
+            self.slots[index].is_none() && self.accepts(index, item)
+        })
+    }
+
+    #[must_use]
+    pub fn free_slots(&self) -> usize {
+        self.slots.iter().filter(|slot| slot.is_none()).count()
+    }
+
+    /// Put as much of `item` as fits in the slot at `index`, stacking
+    /// onto what is there. Returns how many didn't fit
+    pub fn insert_at(&mut self, index: usize, mut item: Item) -> usize {
+        if !self.accepts(index, &item) {
+            return item.amount;
+        }
+        match self.slots.get_mut(index) {
+            Some(Some(slot)) if can_stack(slot, &item) => {
+                add_to_slot(slot, &mut item);
+            }
+            Some(slot @ None) => {
+                let mut placed = item;
+                placed.amount = item.amount.min(item.max_stack);
+                item.amount -= placed.amount;
+                if placed.amount > 0 {
+                    *slot = Some(placed);
+                }
+            }
+            _ => {}
+        }
+        item.amount
+    }
+
+    /// Swap the slots at `a` and `b`. Returns false if either is out
+    /// of range, or its filter won't take the other's item
+    pub fn swap(&mut self, a: usize, b: usize) -> bool {
+        if a >= self.len() || b >= self.len() {
+            return false;
+        }
+        let fits = |item: &Option<Item>, index| {
+            item.is_none_or(|item| self.accepts(index, &item))
+        };
+        if !fits(&self.slots[a], b) || !fits(&self.slots[b], a) {
+            return false;
+        }
+        self.slots.swap(a, b);
+        true
+    }
+
+    /// Move `amount` off the stack at `index` into the first free slot
+    /// that takes it. Returns the new slot, or None if there is no such
+    /// slot or the stack isn't bigger than `amount`
+    pub fn split(&mut self, index: usize, amount: usize) -> Option<usize> {
+        let item = self.get_item(index)?;
+        let size = item.amount;
+        let free = self.first_free_for(item)?;
+        if amount == 0 || amount >= size {
+            return None;
+        }
+        let taken = self.take_amount(index, amount)?;
+        self.slots[free] = Some(taken);
+        Some(free)
+    }
+
+    /// Stack as much of slot `from` onto slot `to` as fits.
+    /// Returns how many moved
+    pub fn merge_into(&mut self, from: usize, to: usize) -> usize {
+        if from == to {
+            return 0;
+        }
+        let Some(item) = self.take_item(from) else {
+            return 0;
+        };
+        let left_over = self.insert_at(to, item);
+        self.put_back(from, item, left_over);
+        item.amount - left_over
+    }
+
+    /// Move slot `from` to slot `to` of `other`, stacking if it can and
+    /// swapping the two if it can't. Returns how many moved
+    pub fn move_to(
+        &mut self,
+        from: usize,
+        other: &mut Inventory,
+        to: usize,
+    ) -> usize {
+        let Some(&item) = self.get_item(from) else {
+            return 0;
+        };
+        if !other.accepts(to, &item) {
+            return 0;
+        }
+        self.slots[from] = None;
+        match other.slots[to] {
+            Some(there)
+                if !can_stack(&there, &item) && self.accepts(from, &there) =>
+            {
+                self.slots[from] = other.slots[to].replace(item);
+                item.amount
+            }
+            _ => {
+                let left_over = other.insert_at(to, item);
+                self.put_back(from, item, left_over);
+                item.amount - left_over
+            }
+        }
+    }
+
+    /// Move slot `index` into wherever it fits in `other`, like a
+    /// shift click. Returns how many moved
+    pub fn quick_transfer(
+        &mut self,
+        index: usize,
+        other: &mut Inventory,
+    ) -> usize {
+        let Some(item) = self.take_item(index) else {
+            return 0;
+        };
+        let left_over = other.add_item(item);
+        self.put_back(index, item, left_over);
+        item.amount - left_over
+    }
+
+    /// Return the `left_over` of `item` that was taken from `index`
+    fn put_back(&mut self, index: usize, mut item: Item, left_over: usize) {
+        item.amount = left_over;
+        if left_over > 0 {
+            self.slots[index] = Some(item);
+        }
+    }
+
+    /// Start a batch of changes that only happen if all of them can
+    pub fn transaction(&mut self) -> Transaction<'_> {
+        Transaction {
+            staged: self.clone(),
+            inventory: self,
+            error: None,
+        }
+    }
+
+    pub fn get_items(&self) -> &[Option<Item>] {
+        &self.slots
+    }
+
+    #[must_use]
+    pub fn get_item(&self, index: usize) -> Option<&Item> {
+        self.slots.get(index)?.as_ref()
+    }
+
+    /// Take up to `amount` from the slot at `index`, emptying it if
+    /// nothing is left. Returns what was taken
+    pub fn take_amount(&mut self, index: usize, amount: usize) -> Option<Item> {
+        let slot = self.slots.get_mut(index)?;
+        let item = slot.as_mut()?;
+        let taken = amount.min(item.amount);
+        if taken == 0 {
+            return None;
+        }
+        let mut result = *item;
+        result.amount = taken;
+        item.amount -= taken;
+        if item.amount == 0 {
+            *slot = None;
+        }
+        Some(result)
+    }
+
+    /// Empty the slot at `index`, returning what was in it
+    pub fn take_item(&mut self, index: usize) -> Option<Item> {
+        self.slots.get_mut(index)?.take()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InventoryError {
+    NotEnough {
+        name: Option<ItemName>, // None for an empty slot
+        wanted: usize,
+        available: usize,
+    },
+    NoSpace {
+        name: ItemName,
+        left_over: usize,
+    },
+}
+
+impl fmt::Display for InventoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InventoryError::NotEnough {
+                name: Some(name),
+                wanted,
+                available,
+            } => write!(f, "needs {wanted} {name:?}, only {available} there"),
+            InventoryError::NotEnough { name: None, .. } => {
+                write!(f, "the slot is empty")
+            }
+            InventoryError::NoSpace { name, left_over } => {
+                write!(f, "no room for {left_over} {name:?}")
+            }
+        }
     }
 }
 
-pub trait HasInventory<const N: usize> {
-    fn inventory(&self) -> &Inventory<N>;
-    fn inventory_mut(&mut self) -> &mut Inventory<N>;
+impl std::error::Error for InventoryError {}
+
+/// Adds and removes that happen all together or not at all. They are
+/// made on a copy, which `commit` writes back if every one of them
+/// worked. Dropping it without committing rolls everything back
+#[derive(Debug)]
+pub struct Transaction<'a> {
+    inventory: &'a mut Inventory,
+    staged: Inventory,
+    error: Option<InventoryError>, // the first thing that didn't work
+}
+
+impl Transaction<'_> {
+    /// Add all of `item`
+    pub fn add(&mut self, item: Item) -> &mut Self {
+        if self.error.is_none() {
+            let left_over = self.staged.add_item(item);
+            if left_over > 0 {
+                self.error = Some(InventoryError::NoSpace {
+                    name: item.name,
+                    left_over,
+                });
+            }
+        }
+        self
+    }
+
+    /// Remove `item.amount` of `item`, from anywhere
+    pub fn remove(&mut self, item: Item) -> &mut Self {
+        if self.error.is_none() && !self.staged.remove_item(item) {
+            self.error = Some(InventoryError::NotEnough {
+                name: Some(item.name),
+                wanted: item.amount,
+                available: self.staged.count(item.name),
+            });
+        }
+        self
+    }
+
+    /// Remove `amount` from the slot at `index`
+    pub fn take(&mut self, index: usize, amount: usize) -> &mut Self {
+        if self.error.is_some() {
+            return self;
+        }
+        let item = self.staged.get_item(index).copied();
+        let available = item.map_or(0, |item| item.amount);
+        if available < amount {
+            self.error = Some(InventoryError::NotEnough {
+                name: item.map(|item| item.name),
+                wanted: amount,
+                available,
+            });
+        } else {
+            self.staged.take_amount(index, amount);
+        }
+        self
+    }
+
+    /// Whether committing now would work
+    pub fn check(&self) -> Result<(), InventoryError> {
+        self.error.map_or(Ok(()), Err)
+    }
+
+    /// What the inventory will look like once committed
+    #[must_use]
+    pub fn preview(&self) -> &Inventory {
+        &self.staged
+    }
+
+    /// Apply every change, or none of them if any didn't work
+    pub fn commit(self) -> Result<(), InventoryError> {
+        self.check()?;
+        *self.inventory = self.staged;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::items::{item_dirt, item_stone, item_wooden_sword};
+
+    #[test]
+    fn remove_item_is_all_or_nothing() {
+        let mut inventory = Inventory::new(4);
+        inventory.add_item(item_stone(5));
+        assert!(!inventory.remove_item(item_stone(6)));
+        assert_eq!(inventory.count(ItemName::Stone), 5);
+        assert!(inventory.remove_item(item_stone(5)));
+        assert_eq!(inventory.count(ItemName::Stone), 0);
+    }
+
+    #[test]
+    fn transaction_commits_every_change() {
+        let mut inventory = Inventory::new(4);
+        inventory.add_item(item_stone(10));
+        let mut transaction = inventory.transaction();
+        transaction.remove(item_stone(4)).add(item_dirt(3));
+        assert_eq!(transaction.preview().count(ItemName::Dirt), 3);
+        transaction.commit().unwrap();
+        assert_eq!(inventory.count(ItemName::Stone), 6);
+        assert_eq!(inventory.count(ItemName::Dirt), 3);
+    }
+
+    #[test]
+    fn failed_transaction_changes_nothing() {
+        let mut inventory = Inventory::new(4);
+        inventory.add_item(item_stone(10));
+        let before = inventory.clone();
+        let mut transaction = inventory.transaction();
+        transaction.add(item_dirt(3)).remove(item_stone(11));
+        assert_eq!(
+            transaction.commit(),
+            Err(InventoryError::NotEnough {
+                name: Some(ItemName::Stone),
+                wanted: 11,
+                available: 10,
+            })
+        );
+        assert_eq!(inventory, before);
+    }
+
+    #[test]
+    fn dropped_transaction_rolls_back() {
+        let mut inventory = Inventory::new(4);
+        inventory.add_item(item_stone(10));
+        let before = inventory.clone();
+        {
+            let mut transaction = inventory.transaction();
+            transaction.remove(item_stone(10)).add(item_dirt(1));
+            assert!(transaction.check().is_ok());
+        }
+        assert_eq!(inventory, before);
+    }
+
+    #[test]
+    fn transaction_add_needs_room_for_all() {
+        let mut inventory = Inventory::new(1);
+        let mut transaction = inventory.transaction();
+        transaction.add(item_stone(100));
+        assert_eq!(
+            transaction.check(),
+            Err(InventoryError::NoSpace {
+                name: ItemName::Stone,
+                left_over: 36,
+            })
+        );
+        assert!(transaction.commit().is_err());
+        assert_eq!(inventory.count(ItemName::Stone), 0);
+    }
+
+    #[test]
+    fn take_from_empty_slot_fails() {
+        let mut inventory = Inventory::new(2);
+        inventory.add_item(item_wooden_sword(1));
+        let mut transaction = inventory.transaction();
+        transaction.take(1, 1);
+        assert_eq!(
+            transaction.check(),
+            Err(InventoryError::NotEnough {
+                name: None,
+                wanted: 1,
+                available: 0,
+            })
+        );
+        // and taking more than the slot has
+        let mut transaction = inventory.transaction();
+        transaction.take(0, 2);
+        assert!(transaction.commit().is_err());
+        assert!(inventory.get_item(0).is_some());
+    }
+
+    #[test]
+    fn later_changes_are_skipped_after_a_failure() {
+        let mut inventory = Inventory::new(2);
+        let mut transaction = inventory.transaction();
+        transaction.take(0, 1).add(item_dirt(1));
+        assert_eq!(transaction.preview().count(ItemName::Dirt), 0);
+    }
+
+    #[test]
+    fn queries() {
+        let mut inventory = Inventory::new(5);
+        inventory.add_item(item_stone(70));
+        inventory.add_item(item_dirt(1));
+        assert_eq!(inventory.count(ItemName::Stone), 70);
+        assert_eq!(inventory.find(ItemName::Stone).collect::<Vec<_>>(), [0, 1]);
+        assert_eq!(inventory.first_free(), Some(3));
+        assert_eq!(inventory.free_slots(), 2);
+    }
+
+    #[test]
+    fn swap_slots() {
+        let mut inventory = Inventory::new(3);
+        inventory.add_item(item_stone(1));
+        assert!(inventory.swap(0, 2));
+        assert!(inventory.get_item(0).is_none());
+        assert_eq!(
+            inventory.get_item(2).map(|item| item.name),
+            Some(ItemName::Stone)
+        );
+        assert!(!inventory.swap(0, 3));
+    }
+
+    #[test]
+    fn split_stack() {
+        let mut inventory = Inventory::new(3);
+        inventory.add_item(item_stone(10));
+        assert_eq!(inventory.split(0, 4), Some(1));
+        assert_eq!(inventory.get_item(0).map(|item| item.amount), Some(6));
+        assert_eq!(inventory.get_item(1).map(|item| item.amount), Some(4));
+        // the whole stack, nothing, or an empty slot can't be split
+        assert_eq!(inventory.split(0, 6), None);
+        assert_eq!(inventory.split(0, 0), None);
+        assert_eq!(inventory.split(2, 1), None);
+    }
+
+    #[test]
+    fn split_needs_a_free_slot() {
+        let mut inventory = Inventory::new(1);
+        inventory.add_item(item_stone(10));
+        assert_eq!(inventory.split(0, 4), None);
+        assert_eq!(inventory.count(ItemName::Stone), 10);
+    }
+
+    #[test]
+    fn merge_into_fills_up_to_max_stack() {
+        let mut inventory = Inventory::new(3);
+        inventory.insert_at(0, item_stone(40));
+        inventory.insert_at(1, item_stone(40));
+        assert_eq!(inventory.merge_into(0, 1), 24);
+        assert_eq!(inventory.get_item(0).map(|item| item.amount), Some(16));
+        assert_eq!(inventory.get_item(1).map(|item| item.amount), Some(64));
+        // different items don't merge
+        inventory.insert_at(2, item_dirt(1));
+        assert_eq!(inventory.merge_into(2, 0), 0);
+        assert_eq!(inventory.merge_into(0, 0), 0);
+    }
+
+    #[test]
+    fn move_to_another_inventory() {
+        let mut bag = Inventory::new(2);
+        let mut chest = Inventory::new(6);
+        bag.add_item(item_stone(10));
+        chest.insert_at(5, item_stone(60));
+        // stacks what fits, the rest stays
+        assert_eq!(bag.move_to(0, &mut chest, 5), 4);
+        assert_eq!(bag.get_item(0).map(|item| item.amount), Some(6));
+        // into an empty slot
+        assert_eq!(bag.move_to(0, &mut chest, 0), 6);
+        assert!(bag.get_item(0).is_none());
+        assert_eq!(bag.move_to(0, &mut chest, 1), 0);
+    }
+
+    #[test]
+    fn move_to_swaps_different_items() {
+        let mut bag = Inventory::new(1);
+        let mut chest = Inventory::new(1);
+        bag.add_item(item_stone(5));
+        chest.add_item(item_dirt(3));
+        assert_eq!(bag.move_to(0, &mut chest, 0), 5);
+        assert_eq!(bag.get_item(0).map(|item| item.name), Some(ItemName::Dirt));
+        assert_eq!(
+            chest.get_item(0).map(|item| item.name),
+            Some(ItemName::Stone)
+        );
+    }
+
+    #[test]
+    fn quick_transfer_keeps_what_does_not_fit() {
+        let mut bag = Inventory::new(1);
+        let mut chest = Inventory::new(1);
+        bag.add_item(item_stone(30));
+        chest.add_item(item_stone(50));
+        assert_eq!(bag.quick_transfer(0, &mut chest), 14);
+        assert_eq!(bag.get_item(0).map(|item| item.amount), Some(16));
+        assert_eq!(chest.count(ItemName::Stone), 64);
+    }
+
+    #[test]
+    fn resize_grows_with_empty_slots() {
+        let mut inventory = Inventory::new(2);
+        assert!(inventory.resize(5).is_empty());
+        assert_eq!(inventory.len(), 5);
+        assert_eq!(inventory.free_slots(), 5);
+        assert_eq!(inventory.filter(4), Some(SlotFilter::Any));
+    }
+
+    #[test]
+    fn resize_shrinking_repacks_then_returns_leftovers() {
+        let mut inventory = Inventory::new(4);
+        inventory.insert_at(0, item_stone(60));
+        inventory.insert_at(2, item_stone(10));
+        inventory.insert_at(3, item_dirt(5));
+        let left_over = inventory.resize(1);
+        assert_eq!(inventory.len(), 1);
+        // 4 of the stone fit on the first stack, the rest is handed back
+        assert_eq!(inventory.count(ItemName::Stone), 64);
+        assert_eq!(left_over.len(), 2);
+        assert_eq!(left_over[0].name, ItemName::Stone);
+        assert_eq!(left_over[0].amount, 6);
+        assert_eq!(left_over[1].name, ItemName::Dirt);
+        assert_eq!(left_over[1].amount, 5);
+    }
+
+    #[test]
+    fn filters_limit_what_goes_in() {
+        let mut inventory = Inventory::new(2);
+        assert!(inventory.set_filter(0, SlotFilter::Only(ItemName::Dirt)));
+        inventory.add_item(item_stone(1));
+        assert!(inventory.get_item(0).is_none());
+        assert_eq!(inventory.insert_at(0, item_stone(3)), 3);
+        inventory.add_item(item_dirt(1));
+        assert_eq!(
+            inventory.get_item(0).map(|item| item.name),
+            Some(ItemName::Dirt)
+        );
+        // can't filter out what is already there, or a slot that isn't
+        assert!(!inventory.set_filter(1, SlotFilter::Type(ItemType::Weapon)));
+        assert!(!inventory.set_filter(2, SlotFilter::Any));
+    }
+
+    #[test]
+    fn swap_respects_filters() {
+        let mut inventory = Inventory::new(2);
+        inventory.set_filter(0, SlotFilter::Type(ItemType::Material));
+        inventory.insert_at(0, item_stone(1));
+        inventory.insert_at(1, item_wooden_sword(1));
+        assert!(!inventory.swap(0, 1));
+        assert_eq!(
+            inventory.get_item(0).map(|item| item.name),
+            Some(ItemName::Stone)
+        );
+        // into an empty slot that takes it is fine
+        inventory.take_item(1);
+        assert!(inventory.swap(0, 1));
+    }
+
+    #[test]
+    fn move_to_respects_filters() {
+        let mut bag = Inventory::new(1);
+        let mut chest = Inventory::new(1);
+        bag.set_filter(0, SlotFilter::Only(ItemName::Stone));
+        bag.add_item(item_stone(5));
+        chest.add_item(item_dirt(3));
+        // the dirt can't come back into the bag, so no swap
+        assert_eq!(bag.move_to(0, &mut chest, 0), 0);
+        assert_eq!(bag.count(ItemName::Stone), 5);
+        assert_eq!(chest.count(ItemName::Dirt), 3);
+        // and the chest slot won't take it at all
+        chest.take_item(0);
+        chest.set_filter(0, SlotFilter::Only(ItemName::Dirt));
+        assert_eq!(bag.move_to(0, &mut chest, 0), 0);
+        assert_eq!(bag.count(ItemName::Stone), 5);
+    }
+
+    #[test]
+    fn split_goes_to_a_slot_that_takes_it() {
+        let mut inventory = Inventory::new(3);
+        inventory.set_filter(1, SlotFilter::Only(ItemName::Dirt));
+        inventory.insert_at(0, item_stone(10));
+        assert_eq!(inventory.split(0, 5), Some(2));
+    }
 }
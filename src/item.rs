@@ -1,10 +1,29 @@
+use crate::{
+    block::{Block, BlockFlag, BlockName},
+    effect::StatusEffect,
+    projectile::ProjectileKind,
+    stats::StatModifier,
+    utils::Direction,
+};
 use derive_builder::Builder;
+use glam::IVec2;
 
 #[derive(Clone, Debug, Copy, PartialEq, Default)]
 pub enum ItemUsage {
     #[default]
     None,
-    Block,
+    Block(BlockName), // placed as this block
+}
+
+/// What clicking does with an item in hand
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub enum ItemAction {
+    Mine,
+    Attack,
+    Place(BlockName),
+    Consume,
+    Equip,
+    None,
 }
 
 #[derive(Clone, Debug, Copy, PartialEq, Default)]
@@ -17,34 +36,201 @@ pub enum ItemName {
     #[default]
     Stone,
     Dirt,
+    WoodenPickaxe,
+    WoodenShovel,
+    WoodenAxe,
+    StoneHammer,
+    StoneDrill,
+    WoodenSword,
+    WoodenBow,
+    StoneSpear,
+    MagicWand,
+    VenomDagger,
+    RegenerationPotion,
+    SpeedPotion,
+    HastePotion,
+    FeatherFallPotion,
+    LeatherCap,
+    LeatherTunic,
+    LeatherLeggings,
+    RunnersRing,
+    MinersCharm,
+    Stash,
+    Backpack,
+}
+
+impl ItemName {
+    pub const ALL: [ItemName; 23] = [
+        ItemName::Stone,
+        ItemName::Dirt,
+        ItemName::WoodenPickaxe,
+        ItemName::WoodenShovel,
+        ItemName::WoodenAxe,
+        ItemName::StoneHammer,
+        ItemName::StoneDrill,
+        ItemName::WoodenSword,
+        ItemName::WoodenBow,
+        ItemName::StoneSpear,
+        ItemName::MagicWand,
+        ItemName::VenomDagger,
+        ItemName::RegenerationPotion,
+        ItemName::SpeedPotion,
+        ItemName::HastePotion,
+        ItemName::FeatherFallPotion,
+        ItemName::LeatherCap,
+        ItemName::LeatherTunic,
+        ItemName::LeatherLeggings,
+        ItemName::RunnersRing,
+        ItemName::MinersCharm,
+        ItemName::Stash,
+        ItemName::Backpack,
+    ];
+
+    /// The name written with `{:?}`, e.g. `WoodenPickaxe`, back again
+    #[must_use]
+    pub fn parse(name: &str) -> Option<ItemName> {
+        Self::ALL
+            .into_iter()
+            .find(|item| format!("{item:?}") == name)
+    }
 }
 
 #[derive(Clone, Debug, Copy, PartialEq, Default)]
 pub enum ItemType {
-    // Weapon,
-    // Tool,
+    Weapon,
+    Tool,
     #[default]
     Material,
-    // Equipment,
+    Consumable,
+    Equipment(EquipmentKind),
+}
+
+/// Which equipment slots an item can go in
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub enum EquipmentKind {
+    Head,
+    Chest,
+    Legs,
+    Accessory, // either accessory slot
+}
+
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct ArmorProps {
+    pub defense: f32, // added to the defense stat while worn
+}
+
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct BagProps {
+    pub slots: usize, // added to the inventory while worn
 }
 
 #[derive(Clone, Debug, Copy, PartialEq)]
 pub struct WeaponProps {
-    damage: f32,   // how hard this hits
-    cooldown: f32, // how fast this attacks
+    pub damage: f32,                  // how hard this hits
+    pub cooldown: f32,                // seconds between swings
+    pub reach: f32, // how far in front of the player the hitbox goes
+    pub knockback: f32, // speed things are pushed away with
+    pub on_hit: Option<StatusEffect>, // applied to whatever it hits
+}
+
+impl WeaponProps {
+    /// `cooldown` in ticks
+    #[must_use]
+    pub fn cooldown_ticks(&self, tick_rate: f32) -> u32 {
+        (self.cooldown * tick_rate).round().max(0.0) as u32
+    }
+}
+
+/// Weapons that shoot (or throw) a projectile instead of swinging
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct RangedProps {
+    pub projectile: ProjectileKind, // what gets fired
+    pub speed: f32,                 // launch speed, units per second
+    pub cooldown: f32,              // seconds between shots
+}
+
+impl RangedProps {
+    /// `cooldown` in ticks
+    #[must_use]
+    pub fn cooldown_ticks(&self, tick_rate: f32) -> u32 {
+        (self.cooldown * tick_rate).round().max(0.0) as u32
+    }
+}
+
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub enum ToolKind {
+    Pickaxe,
+    Shovel,
+    Axe,
+}
+
+impl ToolKind {
+    /// The block flag this kind of tool is made for
+    #[must_use]
+    pub fn block_flag(&self) -> BlockFlag {
+        match self {
+            ToolKind::Pickaxe => BlockFlag::Mine,
+            ToolKind::Shovel => BlockFlag::Dig,
+            ToolKind::Axe => BlockFlag::Chop,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Copy, PartialEq, Default)]
+pub enum SpreadPattern {
+    /// a square around the hit block, `spread` blocks out on every side
+    #[default]
+    Area,
+    /// `spread` blocks behind the hit block, along where the player looks
+    Line,
 }
 
 #[derive(Clone, Debug, Copy, PartialEq)]
 pub struct ToolProps {
-    damage: f32,     // how hard this hits blocks
-    multiplier: f32, // how many more items should you get for breaking
-    spread: f32,     // how many extra blocks do you break (round down always)
+    pub kind: ToolKind,  // which blocks this is good at breaking
+    pub tier: u32,       // compared against `Block::required_level`
+    pub damage: f32,     // how hard this hits blocks
+    pub multiplier: f32, // how many more items should you get for breaking
+    pub spread: f32, // how many extra blocks do you break (round down always)
+    pub pattern: SpreadPattern, // which extra blocks do you break
+}
+
+impl ToolProps {
+    /// Returns if this tool is the right kind for `block`
+    #[must_use]
+    pub fn can_work(&self, block: &Block) -> bool {
+        block.has_flag(self.kind.block_flag())
+    }
+
+    /// Offsets (from the hit block) of the extra blocks this tool breaks.
+    /// Never contains the hit block itself
+    #[must_use]
+    pub fn spread_offsets(&self, look_dir: Direction) -> Vec<IVec2> {
+        let spread = self.spread.max(0.0).floor() as i32;
+        match self.pattern {
+            SpreadPattern::Area => (-spread..=spread)
+                .flat_map(|y| (-spread..=spread).map(move |x| IVec2::new(x, y)))
+                .filter(|offset| *offset != IVec2::ZERO)
+                .collect(),
+            SpreadPattern::Line => {
+                let dir = look_dir.as_ivec2();
+                if dir == IVec2::ZERO {
+                    return Vec::new();
+                }
+                (1..=spread).map(|i| dir * i).collect()
+            }
+        }
+    }
 }
 
 #[derive(Clone, Debug, Copy, PartialEq, Default)]
 pub enum ItemProps {
     Weapon(WeaponProps),
+    Ranged(RangedProps),
     Tool(ToolProps),
+    Potion(StatusEffect), // applied to whoever drinks it
+    Armor(ArmorProps),
+    Bag(BagProps),
     #[default]
     None,
 }
@@ -54,8 +240,13 @@ pub struct Item {
     pub amount: usize,
     pub rarity: ItemRarity,
     pub name: ItemName,
+    pub item_type: ItemType,
     pub color: (u8, u8, u8),
     pub props: ItemProps,
+    #[builder(default)]
+    pub modifiers: &'static [StatModifier], // while held (or worn)
+    #[builder(default)]
+    pub usage: ItemUsage,
 }
 
 impl Item {
@@ -65,6 +256,7 @@ impl Item {
         amount: usize,
         rarity: ItemRarity,
         name: ItemName,
+        item_type: ItemType,
         color: (u8, u8, u8),
         props: ItemProps,
     ) -> Self {
@@ -73,14 +265,41 @@ impl Item {
             amount,
             rarity,
             name,
+            item_type,
             color,
             props,
+            modifiers: &[],
+            usage: ItemUsage::None,
+        }
+    }
+
+    /// What left click does while holding this
+    #[must_use]
+    pub fn primary_action(&self) -> ItemAction {
+        if self.is_weapon() {
+            ItemAction::Attack
+        } else {
+            ItemAction::Mine
+        }
+    }
+
+    /// What right click does while holding this
+    #[must_use]
+    pub fn secondary_action(&self) -> ItemAction {
+        if let ItemUsage::Block(block) = self.usage {
+            ItemAction::Place(block)
+        } else if self.equipment_kind().is_some() {
+            ItemAction::Equip
+        } else if self.potion_effect().is_some() {
+            ItemAction::Consume
+        } else {
+            ItemAction::None
         }
     }
 
     #[must_use]
     pub fn is_weapon(&self) -> bool {
-        matches!(self.props, ItemProps::Weapon(_))
+        matches!(self.props, ItemProps::Weapon(_) | ItemProps::Ranged(_))
     }
 
     #[must_use]
@@ -92,6 +311,52 @@ impl Item {
         }
     }
 
+    #[must_use]
+    pub fn ranged_props(&self) -> Option<&RangedProps> {
+        if let ItemProps::Ranged(r) = &self.props {
+            Some(r)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn potion_effect(&self) -> Option<&StatusEffect> {
+        if let ItemProps::Potion(e) = &self.props {
+            Some(e)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn armor_props(&self) -> Option<&ArmorProps> {
+        if let ItemProps::Armor(a) = &self.props {
+            Some(a)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn bag_props(&self) -> Option<&BagProps> {
+        if let ItemProps::Bag(b) = &self.props {
+            Some(b)
+        } else {
+            None
+        }
+    }
+
+    /// Which kind of equipment slot this goes in, if any
+    #[must_use]
+    pub fn equipment_kind(&self) -> Option<EquipmentKind> {
+        if let ItemType::Equipment(kind) = self.item_type {
+            Some(kind)
+        } else {
+            None
+        }
+    }
+
     #[must_use]
     pub fn tool_props(&self) -> Option<&ToolProps> {
         if let ItemProps::Tool(t) = &self.props {
@@ -1,10 +1,42 @@
-use crate::item::{Item, ItemBuilder, ItemName, ItemProps, ItemRarity};
+use crate::block::BlockName;
+use crate::effects::{
+    effect_feather_fall, effect_mining_haste, effect_poison,
+    effect_regeneration, effect_speed,
+};
+use crate::item::{
+    ArmorProps, BagProps, EquipmentKind, Item, ItemBuilder, ItemName,
+    ItemProps, ItemRarity, ItemType, ItemUsage, RangedProps, SpreadPattern,
+    ToolKind, ToolProps, WeaponProps,
+};
+use crate::projectile::ProjectileKind;
+use crate::stats::{StatModifier, StatName};
 
 #[must_use]
 pub fn item_from_name(item_name: ItemName, amount: usize) -> Item {
     match item_name {
         ItemName::Stone => item_stone(amount),
         ItemName::Dirt => item_dirt(amount),
+        ItemName::WoodenPickaxe => item_wooden_pickaxe(amount),
+        ItemName::WoodenShovel => item_wooden_shovel(amount),
+        ItemName::WoodenAxe => item_wooden_axe(amount),
+        ItemName::StoneHammer => item_stone_hammer(amount),
+        ItemName::StoneDrill => item_stone_drill(amount),
+        ItemName::WoodenSword => item_wooden_sword(amount),
+        ItemName::WoodenBow => item_wooden_bow(amount),
+        ItemName::StoneSpear => item_stone_spear(amount),
+        ItemName::MagicWand => item_magic_wand(amount),
+        ItemName::VenomDagger => item_venom_dagger(amount),
+        ItemName::RegenerationPotion => item_regeneration_potion(amount),
+        ItemName::SpeedPotion => item_speed_potion(amount),
+        ItemName::HastePotion => item_haste_potion(amount),
+        ItemName::FeatherFallPotion => item_feather_fall_potion(amount),
+        ItemName::LeatherCap => item_leather_cap(amount),
+        ItemName::LeatherTunic => item_leather_tunic(amount),
+        ItemName::LeatherLeggings => item_leather_leggings(amount),
+        ItemName::RunnersRing => item_runners_ring(amount),
+        ItemName::MinersCharm => item_miners_charm(amount),
+        ItemName::Backpack => item_backpack(amount),
+        ItemName::Stash => item_stash(amount),
     }
 }
 
@@ -14,9 +46,11 @@ pub fn item_stone(amount: usize) -> Item {
         .max_stack(64)
         .amount(amount)
         .name(ItemName::Stone)
+        .item_type(ItemType::Material)
         .rarity(ItemRarity::Common)
         .color((1, 1, 1))
         .props(ItemProps::None)
+        .usage(ItemUsage::Block(BlockName::Stone))
         .build()
         .unwrap()
 }
@@ -27,9 +61,387 @@ pub fn item_dirt(amount: usize) -> Item {
         .max_stack(64)
         .amount(amount)
         .name(ItemName::Dirt)
+        .item_type(ItemType::Material)
         .rarity(ItemRarity::Common)
         .color((160, 82, 45))
         .props(ItemProps::None)
+        .usage(ItemUsage::Block(BlockName::Dirt))
+        .build()
+        .unwrap()
+}
+
+#[must_use]
+pub fn item_wooden_pickaxe(amount: usize) -> Item {
+    ItemBuilder::default()
+        .max_stack(1)
+        .amount(amount)
+        .name(ItemName::WoodenPickaxe)
+        .item_type(ItemType::Tool)
+        .rarity(ItemRarity::Common)
+        .color((196, 164, 132))
+        .props(ItemProps::Tool(ToolProps {
+            kind: ToolKind::Pickaxe,
+            tier: 1,
+            damage: 35.0,
+            multiplier: 1.0,
+            spread: 0.0,
+            pattern: SpreadPattern::Area,
+        }))
+        .build()
+        .unwrap()
+}
+
+#[must_use]
+pub fn item_wooden_shovel(amount: usize) -> Item {
+    ItemBuilder::default()
+        .max_stack(1)
+        .amount(amount)
+        .name(ItemName::WoodenShovel)
+        .item_type(ItemType::Tool)
+        .rarity(ItemRarity::Common)
+        .color((181, 148, 116))
+        .props(ItemProps::Tool(ToolProps {
+            kind: ToolKind::Shovel,
+            tier: 1,
+            damage: 35.0,
+            multiplier: 1.0,
+            spread: 0.0,
+            pattern: SpreadPattern::Area,
+        }))
+        .build()
+        .unwrap()
+}
+
+#[must_use]
+pub fn item_wooden_axe(amount: usize) -> Item {
+    ItemBuilder::default()
+        .max_stack(1)
+        .amount(amount)
+        .name(ItemName::WoodenAxe)
+        .item_type(ItemType::Tool)
+        .rarity(ItemRarity::Common)
+        .color((166, 133, 100))
+        .props(ItemProps::Tool(ToolProps {
+            kind: ToolKind::Axe,
+            tier: 1,
+            damage: 35.0,
+            multiplier: 1.0,
+            spread: 0.0,
+            pattern: SpreadPattern::Area,
+        }))
+        .build()
+        .unwrap()
+}
+
+// heavy, slows you down while held
+const STONE_HAMMER_MODIFIERS: &[StatModifier] =
+    &[StatModifier::multiply(StatName::MoveSpeed, 0.85)];
+
+#[must_use]
+pub fn item_stone_hammer(amount: usize) -> Item {
+    ItemBuilder::default()
+        .max_stack(1)
+        .amount(amount)
+        .name(ItemName::StoneHammer)
+        .item_type(ItemType::Tool)
+        .rarity(ItemRarity::Common)
+        .color((120, 120, 120))
+        .props(ItemProps::Tool(ToolProps {
+            kind: ToolKind::Pickaxe,
+            tier: 2,
+            damage: 25.0,
+            multiplier: 1.0,
+            spread: 1.0,
+            pattern: SpreadPattern::Area,
+        }))
+        .modifiers(STONE_HAMMER_MODIFIERS)
+        .build()
+        .unwrap()
+}
+
+#[must_use]
+pub fn item_stone_drill(amount: usize) -> Item {
+    ItemBuilder::default()
+        .max_stack(1)
+        .amount(amount)
+        .name(ItemName::StoneDrill)
+        .item_type(ItemType::Tool)
+        .rarity(ItemRarity::Common)
+        .color((100, 100, 110))
+        .props(ItemProps::Tool(ToolProps {
+            kind: ToolKind::Pickaxe,
+            tier: 2,
+            damage: 30.0,
+            multiplier: 1.0,
+            spread: 2.0,
+            pattern: SpreadPattern::Line,
+        }))
+        .build()
+        .unwrap()
+}
+
+#[must_use]
+pub fn item_wooden_sword(amount: usize) -> Item {
+    ItemBuilder::default()
+        .max_stack(1)
+        .amount(amount)
+        .name(ItemName::WoodenSword)
+        .item_type(ItemType::Weapon)
+        .rarity(ItemRarity::Common)
+        .color((181, 136, 99))
+        .props(ItemProps::Weapon(WeaponProps {
+            damage: 20.0,
+            cooldown: 0.5,
+            reach: 1.5,
+            knockback: 12.0,
+            on_hit: None,
+        }))
+        .build()
+        .unwrap()
+}
+
+#[must_use]
+pub fn item_wooden_bow(amount: usize) -> Item {
+    ItemBuilder::default()
+        .max_stack(1)
+        .amount(amount)
+        .name(ItemName::WoodenBow)
+        .item_type(ItemType::Weapon)
+        .rarity(ItemRarity::Common)
+        .color((150, 111, 51))
+        .props(ItemProps::Ranged(RangedProps {
+            projectile: ProjectileKind::Arrow,
+            speed: 30.0,
+            cooldown: 0.6,
+        }))
+        .build()
+        .unwrap()
+}
+
+// long handle, reach further while held
+const STONE_SPEAR_MODIFIERS: &[StatModifier] =
+    &[StatModifier::add(StatName::Reach, 0.25)];
+
+#[must_use]
+pub fn item_stone_spear(amount: usize) -> Item {
+    ItemBuilder::default()
+        .max_stack(1)
+        .amount(amount)
+        .name(ItemName::StoneSpear)
+        .item_type(ItemType::Weapon)
+        .rarity(ItemRarity::Common)
+        .color((128, 128, 128))
+        .props(ItemProps::Ranged(RangedProps {
+            projectile: ProjectileKind::Spear,
+            speed: 22.0,
+            cooldown: 1.0,
+        }))
+        .modifiers(STONE_SPEAR_MODIFIERS)
+        .build()
+        .unwrap()
+}
+
+#[must_use]
+pub fn item_magic_wand(amount: usize) -> Item {
+    ItemBuilder::default()
+        .max_stack(1)
+        .amount(amount)
+        .name(ItemName::MagicWand)
+        .item_type(ItemType::Weapon)
+        .rarity(ItemRarity::Common)
+        .color((148, 0, 211))
+        .props(ItemProps::Ranged(RangedProps {
+            projectile: ProjectileKind::MagicBolt,
+            speed: 18.0,
+            cooldown: 0.4,
+        }))
+        .build()
+        .unwrap()
+}
+
+#[must_use]
+pub fn item_venom_dagger(amount: usize) -> Item {
+    ItemBuilder::default()
+        .max_stack(1)
+        .amount(amount)
+        .name(ItemName::VenomDagger)
+        .item_type(ItemType::Weapon)
+        .rarity(ItemRarity::Common)
+        .color((107, 142, 35))
+        .props(ItemProps::Weapon(WeaponProps {
+            damage: 10.0,
+            cooldown: 0.3,
+            reach: 1.0,
+            knockback: 4.0,
+            on_hit: Some(effect_poison(60 * 5, 1)),
+        }))
+        .build()
+        .unwrap()
+}
+
+#[must_use]
+pub fn item_regeneration_potion(amount: usize) -> Item {
+    ItemBuilder::default()
+        .max_stack(16)
+        .amount(amount)
+        .name(ItemName::RegenerationPotion)
+        .item_type(ItemType::Consumable)
+        .rarity(ItemRarity::Common)
+        .color((255, 105, 180))
+        .props(ItemProps::Potion(effect_regeneration(60 * 10, 1)))
+        .build()
+        .unwrap()
+}
+
+#[must_use]
+pub fn item_speed_potion(amount: usize) -> Item {
+    ItemBuilder::default()
+        .max_stack(16)
+        .amount(amount)
+        .name(ItemName::SpeedPotion)
+        .item_type(ItemType::Consumable)
+        .rarity(ItemRarity::Common)
+        .color((0, 191, 255))
+        .props(ItemProps::Potion(effect_speed(60 * 30, 1)))
+        .build()
+        .unwrap()
+}
+
+#[must_use]
+pub fn item_feather_fall_potion(amount: usize) -> Item {
+    ItemBuilder::default()
+        .max_stack(16)
+        .amount(amount)
+        .name(ItemName::FeatherFallPotion)
+        .item_type(ItemType::Consumable)
+        .rarity(ItemRarity::Common)
+        .color((200, 230, 255))
+        .props(ItemProps::Potion(effect_feather_fall(60 * 30, 1)))
+        .build()
+        .unwrap()
+}
+
+#[must_use]
+pub fn item_haste_potion(amount: usize) -> Item {
+    ItemBuilder::default()
+        .max_stack(16)
+        .amount(amount)
+        .name(ItemName::HastePotion)
+        .item_type(ItemType::Consumable)
+        .rarity(ItemRarity::Common)
+        .color((255, 215, 0))
+        .props(ItemProps::Potion(effect_mining_haste(60 * 30, 1)))
+        .build()
+        .unwrap()
+}
+
+#[must_use]
+pub fn item_leather_cap(amount: usize) -> Item {
+    ItemBuilder::default()
+        .max_stack(1)
+        .amount(amount)
+        .name(ItemName::LeatherCap)
+        .item_type(ItemType::Equipment(EquipmentKind::Head))
+        .rarity(ItemRarity::Common)
+        .color((150, 95, 50))
+        .props(ItemProps::Armor(ArmorProps { defense: 2.0 }))
+        .build()
+        .unwrap()
+}
+
+#[must_use]
+pub fn item_leather_tunic(amount: usize) -> Item {
+    ItemBuilder::default()
+        .max_stack(1)
+        .amount(amount)
+        .name(ItemName::LeatherTunic)
+        .item_type(ItemType::Equipment(EquipmentKind::Chest))
+        .rarity(ItemRarity::Common)
+        .color((140, 85, 45))
+        .props(ItemProps::Armor(ArmorProps { defense: 4.0 }))
+        .build()
+        .unwrap()
+}
+
+const LEATHER_LEGGINGS_MODIFIERS: &[StatModifier] =
+    &[StatModifier::multiply(StatName::FallDamage, 0.8)];
+
+#[must_use]
+pub fn item_leather_leggings(amount: usize) -> Item {
+    ItemBuilder::default()
+        .max_stack(1)
+        .amount(amount)
+        .name(ItemName::LeatherLeggings)
+        .item_type(ItemType::Equipment(EquipmentKind::Legs))
+        .rarity(ItemRarity::Common)
+        .color((130, 80, 40))
+        .props(ItemProps::Armor(ArmorProps { defense: 3.0 }))
+        .modifiers(LEATHER_LEGGINGS_MODIFIERS)
+        .build()
+        .unwrap()
+}
+
+const RUNNERS_RING_MODIFIERS: &[StatModifier] =
+    &[StatModifier::add(StatName::MoveSpeed, 0.1)];
+
+#[must_use]
+pub fn item_runners_ring(amount: usize) -> Item {
+    ItemBuilder::default()
+        .max_stack(1)
+        .amount(amount)
+        .name(ItemName::RunnersRing)
+        .item_type(ItemType::Equipment(EquipmentKind::Accessory))
+        .rarity(ItemRarity::Common)
+        .color((192, 192, 210))
+        .props(ItemProps::None)
+        .modifiers(RUNNERS_RING_MODIFIERS)
+        .build()
+        .unwrap()
+}
+
+const MINERS_CHARM_MODIFIERS: &[StatModifier] =
+    &[StatModifier::add(StatName::MiningSpeed, 0.15)];
+
+#[must_use]
+pub fn item_miners_charm(amount: usize) -> Item {
+    ItemBuilder::default()
+        .max_stack(1)
+        .amount(amount)
+        .name(ItemName::MinersCharm)
+        .item_type(ItemType::Equipment(EquipmentKind::Accessory))
+        .rarity(ItemRarity::Common)
+        .color((80, 200, 120))
+        .props(ItemProps::None)
+        .modifiers(MINERS_CHARM_MODIFIERS)
+        .build()
+        .unwrap()
+}
+
+#[must_use]
+pub fn item_backpack(amount: usize) -> Item {
+    ItemBuilder::default()
+        .max_stack(1)
+        .amount(amount)
+        .name(ItemName::Backpack)
+        .item_type(ItemType::Equipment(EquipmentKind::Accessory))
+        .rarity(ItemRarity::Common)
+        .color((110, 80, 40))
+        .props(ItemProps::Bag(BagProps { slots: 10 }))
+        .build()
+        .unwrap()
+}
+
+#[must_use]
+pub fn item_stash(amount: usize) -> Item {
+    ItemBuilder::default()
+        .max_stack(8)
+        .amount(amount)
+        .name(ItemName::Stash)
+        .item_type(ItemType::Material)
+        .rarity(ItemRarity::Common)
+        .color((120, 72, 160))
+        .props(ItemProps::None)
+        .usage(ItemUsage::Block(BlockName::Stash))
         .build()
         .unwrap()
 }
@@ -1,13 +1,37 @@
 pub mod block;
 pub mod blocks;
 pub mod camera;
+pub mod combat;
+pub mod components;
+pub mod damage;
+pub mod dropped_item;
+pub mod effect;
+pub mod effects;
+pub mod enemies;
+pub mod enemy;
+pub mod equipment;
+pub mod experience;
 pub mod game;
 pub mod input;
 pub mod inventory;
 pub mod item;
 pub mod items;
+pub mod loot;
+pub mod loot_tables;
+pub mod movement;
+pub mod pathfinding;
+pub mod physics;
 pub mod player;
+pub mod projectile;
+pub mod projectiles;
 pub mod range;
 pub mod render;
+pub mod save;
+pub mod schedule;
+pub mod skill;
+pub mod skills;
+pub mod stash;
+pub mod stats;
+pub mod systems;
 pub mod utils;
 pub mod world;
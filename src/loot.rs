@@ -0,0 +1,198 @@
+use crate::{item::Item, item::ItemName, items::item_from_name, range::Range};
+use rand::Rng;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LootEntry {
+    pub item: Option<ItemName>, // `None` means this roll drops nothing
+    pub weight: u32,            // relative to the other entries in the table
+    pub amount: Range,          // before any multipliers
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LootTable {
+    pub rolls: u32, // how many entries get picked
+    pub entries: &'static [LootEntry],
+}
+
+/// Rounds down, but the fractional part is the chance of rounding up instead.
+/// Keeps small multipliers (like 1.25) meaningful on average
+fn round_randomly(value: f32, rng: &mut impl Rng) -> usize {
+    let whole = value.floor();
+    let extra = if rng.random::<f32>() < value - whole {
+        1
+    } else {
+        0
+    };
+    whole.max(0.0) as usize + extra
+}
+
+impl LootTable {
+    fn pick(&self, rng: &mut impl Rng) -> Option<&LootEntry> {
+        let total: u32 = self.entries.iter().map(|e| e.weight).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut roll = rng.random_range(0..total);
+        for entry in self.entries {
+            if roll < entry.weight {
+                return Some(entry);
+            }
+            roll -= entry.weight;
+        }
+        None
+    }
+
+    /// Roll the table, scaling every amount by `multiplier`.
+    /// Pass a seeded rng to get the same drops every time
+    pub fn roll(&self, rng: &mut impl Rng, multiplier: f32) -> Vec<Item> {
+        let mut drops = Vec::new();
+        for _ in 0..self.rolls {
+            let Some(entry) = self.pick(rng) else {
+                continue;
+            };
+            let Some(name) = entry.item else {
+                continue;
+            };
+            let amount =
+                round_randomly(entry.amount.sample(rng) * multiplier, rng);
+            if amount > 0 {
+                drops.push(item_from_name(name, amount));
+            }
+        }
+        drops
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    const MIXED: LootTable = LootTable {
+        rolls: 4,
+        entries: &[
+            LootEntry {
+                item: Some(ItemName::Stone),
+                weight: 3,
+                amount: Range::new(1.0, 4.0),
+            },
+            LootEntry {
+                item: Some(ItemName::Dirt),
+                weight: 1,
+                amount: Range::new(2.0, 2.0),
+            },
+            LootEntry {
+                item: None,
+                weight: 2,
+                amount: Range::exactly(1.0),
+            },
+        ],
+    };
+
+    #[test]
+    fn same_seed_same_drops() {
+        let first = MIXED.roll(&mut StdRng::seed_from_u64(42), 1.0);
+        let second = MIXED.roll(&mut StdRng::seed_from_u64(42), 1.0);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn zero_weight_never_drops() {
+        const TABLE: LootTable = LootTable {
+            rolls: 1,
+            entries: &[
+                LootEntry {
+                    item: Some(ItemName::Stone),
+                    weight: 0,
+                    amount: Range::exactly(1.0),
+                },
+                LootEntry {
+                    item: Some(ItemName::Dirt),
+                    weight: 1,
+                    amount: Range::exactly(1.0),
+                },
+            ],
+        };
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..500 {
+            for item in TABLE.roll(&mut rng, 1.0) {
+                assert_eq!(item.name, ItemName::Dirt);
+            }
+        }
+    }
+
+    #[test]
+    fn all_zero_weights_drop_nothing() {
+        const TABLE: LootTable = LootTable {
+            rolls: 3,
+            entries: &[LootEntry {
+                item: Some(ItemName::Stone),
+                weight: 0,
+                amount: Range::exactly(1.0),
+            }],
+        };
+        assert!(TABLE.roll(&mut StdRng::seed_from_u64(1), 1.0).is_empty());
+    }
+
+    #[test]
+    fn amounts_stay_in_range() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..500 {
+            let value = Range::new(1.0, 4.0).sample(&mut rng);
+            assert!((1.0..=4.0).contains(&value));
+        }
+        for _ in 0..200 {
+            for item in MIXED.roll(&mut rng, 1.0) {
+                let bounds = match item.name {
+                    ItemName::Stone => 1..=4,
+                    _ => 2..=2,
+                };
+                assert!(bounds.contains(&item.amount), "{item:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn backwards_range_is_its_min() {
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(Range::new(3.0, 1.0).sample(&mut rng), 3.0);
+    }
+
+    #[test]
+    fn round_randomly_stays_between_neighbours() {
+        let mut rng = StdRng::seed_from_u64(5);
+        assert_eq!(round_randomly(2.0, &mut rng), 2);
+        assert_eq!(round_randomly(-1.0, &mut rng), 0);
+        let rolls = 4000;
+        let total: usize =
+            (0..rolls).map(|_| round_randomly(1.25, &mut rng)).sum();
+        // every roll is 1 or 2, and a quarter of them round up
+        let average = total as f32 / rolls as f32;
+        assert!((1.2..1.3).contains(&average), "{average}");
+    }
+
+    #[test]
+    fn multiplier_scales_amounts() {
+        const ONE_STONE: LootTable = LootTable {
+            rolls: 1,
+            entries: &[LootEntry {
+                item: Some(ItemName::Stone),
+                weight: 1,
+                amount: Range::exactly(3.0),
+            }],
+        };
+        let mut rng = StdRng::seed_from_u64(9);
+        assert_eq!(ONE_STONE.roll(&mut rng, 1.0)[0].amount, 3);
+        // a whole multiplier is exact
+        let drops = ONE_STONE.roll(&mut rng, 2.0);
+        assert_eq!(drops.len(), 1);
+        assert_eq!(drops[0].amount, 6);
+        // 3 * 1.5 = 4.5, so 4 or 5
+        for _ in 0..100 {
+            let amount = ONE_STONE.roll(&mut rng, 1.5)[0].amount;
+            assert!((4..=5).contains(&amount));
+        }
+        // nothing left after a 0 multiplier
+        assert!(ONE_STONE.roll(&mut rng, 0.0).is_empty());
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{
+    item::ItemName,
+    loot::{LootEntry, LootTable},
+    range::Range,
+};
+
+pub const LOOT_DIRT: LootTable = LootTable {
+    rolls: 1,
+    entries: &[
+        LootEntry {
+            item: Some(ItemName::Dirt),
+            weight: 9,
+            amount: Range::exactly(1.0),
+        },
+        LootEntry {
+            item: Some(ItemName::Dirt),
+            weight: 1,
+            amount: Range::exactly(2.0),
+        },
+    ],
+};
+
+pub const LOOT_STONE: LootTable = LootTable {
+    rolls: 1,
+    entries: &[LootEntry {
+        item: Some(ItemName::Stone),
+        weight: 1,
+        amount: Range::exactly(1.0),
+    }],
+};
+
+pub const LOOT_STASH: LootTable = LootTable {
+    rolls: 1,
+    entries: &[LootEntry {
+        item: Some(ItemName::Stash),
+        weight: 1,
+        amount: Range::exactly(1.0),
+    }],
+};
+
+pub const LOOT_CRAWLER: LootTable = LootTable {
+    rolls: 1,
+    entries: &[
+        LootEntry {
+            item: Some(ItemName::Dirt),
+            weight: 3,
+            amount: Range::new(1.0, 3.0),
+        },
+        LootEntry {
+            item: None,
+            weight: 1,
+            amount: Range::exactly(0.0),
+        },
+    ],
+};
+
+pub const LOOT_BAT: LootTable = LootTable {
+    rolls: 1,
+    entries: &[
+        LootEntry {
+            item: Some(ItemName::Stone),
+            weight: 1,
+            amount: Range::exactly(1.0),
+        },
+        LootEntry {
+            item: None,
+            weight: 2,
+            amount: Range::exactly(0.0),
+        },
+    ],
+};
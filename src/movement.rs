@@ -0,0 +1,106 @@
+use crate::{
+    components::{Collider, Velocity},
+    physics::apply_friction,
+    utils::Direction,
+};
+
+/// Tunable numbers for how an entity runs and jumps
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MovementConfig {
+    pub acceleration: f32,      // units per second per second
+    pub max_speed: f32,         // max horizontal speed
+    pub friction: f32,          // slow down when not moving, units per second²
+    pub jump_velocity: f32,     // upwards speed at the start of a jump
+    pub jump_cut: f32,          // scales upwards speed on an early release
+    pub coyote_ticks: u32, // ticks after leaving a ledge you can still jump
+    pub jump_buffer_ticks: u32, // ticks before landing a jump press still counts
+}
+
+impl Default for MovementConfig {
+    fn default() -> Self {
+        Self {
+            acceleration: 60.0,
+            max_speed: 20.0,
+            friction: 15.0,
+            jump_velocity: 20.0,
+            jump_cut: 0.5,
+            coyote_ticks: 6,
+            jump_buffer_ticks: 6,
+        }
+    }
+}
+
+/// Bookkeeping for coyote time, jump buffering and variable jump height
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct JumpState {
+    pub ticks_since_ground: u32,
+    pub buffered_ticks: u32, // how much longer a jump press is remembered
+    pub jumping: bool,       // rising from a jump that can still be cut short
+}
+
+/// Accelerate towards `direction`, or slow down if it is `None`.
+/// `speed` scales max speed and acceleration, 1 is normal
+pub fn try_move(
+    velocity: &mut Velocity,
+    config: &MovementConfig,
+    direction: Direction,
+    speed: f32,
+    fps: f32,
+) {
+    let acceleration = config.acceleration * speed / fps;
+    let max_speed = config.max_speed * speed;
+
+    match direction {
+        Direction::Left => {
+            velocity.0.x = (velocity.0.x - acceleration).max(-max_speed);
+        }
+        Direction::Right => {
+            velocity.0.x = (velocity.0.x + acceleration).min(max_speed);
+        }
+        _ => apply_friction(&mut velocity.0, config.friction, fps),
+    }
+}
+
+/// Run once per tick. `pressed` is if jump was pressed this tick,
+/// `held` if it is still held down. `height` scales how high it goes
+pub fn update_jump(
+    velocity: &mut Velocity,
+    collider: &Collider,
+    config: &MovementConfig,
+    jump: &mut JumpState,
+    pressed: bool,
+    held: bool,
+    height: f32,
+) {
+    if collider.on_ground() {
+        jump.ticks_since_ground = 0;
+    } else {
+        jump.ticks_since_ground = jump.ticks_since_ground.saturating_add(1);
+    }
+    if velocity.0.y >= 0.0 {
+        jump.jumping = false;
+    }
+
+    if pressed {
+        jump.buffered_ticks = config.jump_buffer_ticks + 1;
+    }
+    let can_jump =
+        !jump.jumping && jump.ticks_since_ground <= config.coyote_ticks;
+    // debuffs can push the height to 0 or below, no jump at all then
+    let height = height.max(0.0);
+    if jump.buffered_ticks > 0 && can_jump && height > 0.0 {
+        // jump height goes with the square of the launch speed
+        velocity.0.y = -config.jump_velocity * height.sqrt();
+        jump.jumping = true;
+        jump.buffered_ticks = 0;
+        // no second jump from the same coyote window
+        jump.ticks_since_ground = config.coyote_ticks + 1;
+    }
+    jump.buffered_ticks = jump.buffered_ticks.saturating_sub(1);
+
+    // let go early, jump lower
+    if jump.jumping && !held {
+        velocity.0.y *= config.jump_cut;
+        jump.jumping = false;
+    }
+}
@@ -0,0 +1,331 @@
+use crate::world::World;
+use glam::{IVec2, Vec2};
+use hecs::World as HecsWorld;
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+};
+
+/// Tiles from start to goal, both included
+pub type Path = Vec<IVec2>;
+
+/// The tile a box at `pos` (top left) of `size` is standing in
+#[must_use]
+pub fn feet_tile(pos: Vec2, size: Vec2) -> IVec2 {
+    // half a tile up, so standing flush on the ground counts as above it
+    (pos + Vec2::new(size.x / 2.0, size.y - 0.5))
+        .floor()
+        .as_ivec2()
+}
+
+/// What an agent can do while moving through the tile grid.
+/// Nodes are the tile its feet are in; its body goes up `height` tiles
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PathConfig {
+    pub height: i32,      // tiles tall
+    pub jump_height: i32, // tiles it can jump up onto
+    pub max_fall: i32,    // tiles it is willing to drop down
+    pub flying: bool,     // goes anywhere open, ignoring ground
+}
+
+impl Default for PathConfig {
+    fn default() -> Self {
+        Self {
+            height: 1,
+            jump_height: 1,
+            max_fall: 3,
+            flying: false,
+        }
+    }
+}
+
+// stop searching after this many nodes, the goal is probably unreachable
+const MAX_SEARCH_NODES: usize = 4096;
+
+fn is_open(map: &World, x: i32, y: i32) -> bool {
+    // unloaded tiles are never walked through
+    map.get_block(x, y).is_some_and(|block| !block.can_collide)
+}
+
+fn is_ground(map: &World, x: i32, y: i32) -> bool {
+    map.get_block(x, y).is_some_and(|block| block.can_collide)
+}
+
+/// Does the whole body fit with its feet at `pos`
+fn fits(map: &World, config: &PathConfig, pos: IVec2) -> bool {
+    (0..config.height).all(|i| is_open(map, pos.x, pos.y - i))
+}
+
+/// Could the agent stay at `pos` without falling
+fn is_supported(map: &World, config: &PathConfig, pos: IVec2) -> bool {
+    config.flying || is_ground(map, pos.x, pos.y + 1)
+}
+
+/// Every node reachable from `pos` in one move, with what it costs
+fn neighbours(
+    map: &World,
+    config: &PathConfig,
+    pos: IVec2,
+) -> Vec<(IVec2, u32)> {
+    let mut out = Vec::new();
+    if config.flying {
+        for step in [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y] {
+            if fits(map, config, pos + step) {
+                out.push((pos + step, 1));
+            }
+        }
+        return out;
+    }
+
+    for dx in [-1, 1] {
+        let side = pos + IVec2::new(dx, 0);
+        if fits(map, config, side) {
+            if is_supported(map, config, side) {
+                out.push((side, 1));
+                continue;
+            }
+            // walk off the edge and land somewhere below
+            for fall in 1..=config.max_fall {
+                let below = side + IVec2::new(0, fall);
+                if !fits(map, config, below) {
+                    break;
+                }
+                if is_supported(map, config, below) {
+                    out.push((below, 1 + fall as u32));
+                    break;
+                }
+            }
+        } else {
+            // jump up onto whatever is in the way, if there is headroom
+            for jump in 1..=config.jump_height {
+                if !fits(map, config, pos - IVec2::new(0, jump)) {
+                    break;
+                }
+                let above = side - IVec2::new(0, jump);
+                if fits(map, config, above) && is_supported(map, config, above)
+                {
+                    out.push((above, 1 + 2 * jump as u32));
+                    break;
+                }
+            }
+        }
+    }
+    out
+}
+
+fn heuristic(a: IVec2, b: IVec2) -> u32 {
+    let d = (a - b).abs();
+    (d.x + d.y) as u32
+}
+
+/// Cheapest path from `start` to `goal` for an agent with `config`,
+/// or `None` if there isn't one (close enough to search)
+#[must_use]
+pub fn find_path(
+    map: &World,
+    config: &PathConfig,
+    start: IVec2,
+    goal: IVec2,
+) -> Option<Path> {
+    let mut open = BinaryHeap::new();
+    let mut came_from: HashMap<IVec2, IVec2> = HashMap::new();
+    let mut cost: HashMap<IVec2, u32> = HashMap::new();
+
+    open.push(Reverse((heuristic(start, goal), start.x, start.y)));
+    cost.insert(start, 0);
+
+    let mut searched = 0;
+    while let Some(Reverse((_, x, y))) = open.pop() {
+        let current = IVec2::new(x, y);
+        if current == goal {
+            let mut path = vec![current];
+            let mut node = current;
+            while let Some(&previous) = came_from.get(&node) {
+                path.push(previous);
+                node = previous;
+            }
+            path.reverse();
+            return Some(path);
+        }
+        searched += 1;
+        if searched > MAX_SEARCH_NODES {
+            return None;
+        }
+
+        let current_cost = cost[&current];
+        for (next, step_cost) in neighbours(map, config, current) {
+            let next_cost = current_cost + step_cost;
+            if cost.get(&next).is_some_and(|&known| known <= next_cost) {
+                continue;
+            }
+            cost.insert(next, next_cost);
+            came_from.insert(next, current);
+            open.push(Reverse((
+                next_cost + heuristic(next, goal),
+                next.x,
+                next.y,
+            )));
+        }
+    }
+    None
+}
+
+/// A cached path towards a goal, for an entity that walks it
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PathFollower {
+    pub config: PathConfig,
+    pub goal: Option<IVec2>,
+    pub path: Path, // empty if there is no (valid) path right now
+    pub repath_ticks: u32, // ticks before we are allowed to search again
+}
+
+impl PathFollower {
+    const REPATH_DELAY: u32 = 30;
+
+    #[must_use]
+    pub fn new(config: PathConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Does the path go through (or stand on) the block at `changed`
+    #[must_use]
+    pub fn is_affected_by(&self, changed: IVec2) -> bool {
+        self.path.iter().any(|tile| {
+            tile.x == changed.x
+                && changed.y <= tile.y + 1
+                && changed.y > tile.y - self.config.height
+        })
+    }
+
+    /// The next tile to head for from `current`, searching for a new path
+    /// if the goal moved or the old one was thrown away
+    pub fn next_step(
+        &mut self,
+        map: &World,
+        current: IVec2,
+        goal: IVec2,
+    ) -> Option<IVec2> {
+        self.repath_ticks = self.repath_ticks.saturating_sub(1);
+
+        let on_path = self.path.iter().position(|&tile| tile == current);
+        let stale = self.goal != Some(goal) || on_path.is_none();
+        if stale && self.repath_ticks == 0 {
+            self.goal = Some(goal);
+            self.path =
+                find_path(map, &self.config, current, goal).unwrap_or_default();
+            self.repath_ticks = Self::REPATH_DELAY;
+        }
+
+        // forget the part we already walked
+        let index = self.path.iter().position(|&tile| tile == current)?;
+        self.path.drain(..index);
+        self.path.get(1).copied()
+    }
+}
+
+/// Throw away every cached path that goes through a block that changed
+pub fn invalidate_paths_system(ecs: &mut HecsWorld, map: &mut World) {
+    let changed = map.take_changed_blocks();
+    if changed.is_empty() {
+        return;
+    }
+    for (_, follower) in ecs.query_mut::<&mut PathFollower>() {
+        if changed.iter().any(|&pos| follower.is_affected_by(pos)) {
+            follower.path.clear();
+            follower.repath_ticks = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::BlockName;
+
+    /// A dirt floor at y = 10, from x = 0 to 19, and `extra` blocks
+    fn floor_with(extra: &[IVec2]) -> World {
+        let blocks: Vec<_> = (0..20)
+            .map(|x| IVec2::new(x, 10))
+            .chain(extra.iter().copied())
+            .map(|pos| (pos, BlockName::Dirt))
+            .collect();
+        World::with_blocks(&blocks)
+    }
+
+    const START: IVec2 = IVec2::new(1, 9);
+    const GOAL: IVec2 = IVec2::new(15, 9);
+
+    #[test]
+    fn walks_along_the_floor() {
+        let map = floor_with(&[]);
+        let path = find_path(&map, &PathConfig::default(), START, GOAL);
+        let path = path.expect("the floor is flat");
+        assert_eq!(path.first(), Some(&START));
+        assert_eq!(path.last(), Some(&GOAL));
+        assert_eq!(path.len(), 15);
+        assert!(path.iter().all(|tile| tile.y == 9));
+    }
+
+    #[test]
+    fn wall_is_unreachable() {
+        // up to the top of the loaded area, so there is no way over
+        let wall: Vec<_> = (0..10).map(|y| IVec2::new(8, y)).collect();
+        let map = floor_with(&wall);
+        assert_eq!(find_path(&map, &PathConfig::default(), START, GOAL), None);
+    }
+
+    #[test]
+    fn jumps_up_to_jump_height() {
+        let step = [IVec2::new(8, 9), IVec2::new(8, 8)];
+        let map = floor_with(&step);
+        let mut config = PathConfig::default();
+        assert_eq!(find_path(&map, &config, START, GOAL), None);
+
+        config.jump_height = 2;
+        let path = find_path(&map, &config, START, GOAL).unwrap();
+        assert!(path.contains(&IVec2::new(8, 7)));
+    }
+
+    #[test]
+    fn falls_up_to_max_fall() {
+        // standing on a pillar, 4 tiles above the floor
+        let pillar: Vec<_> = (6..10).map(|y| IVec2::new(5, y)).collect();
+        let map = floor_with(&pillar);
+        let start = IVec2::new(5, 5);
+        let mut config = PathConfig::default();
+        assert_eq!(find_path(&map, &config, start, GOAL), None);
+
+        config.max_fall = 4;
+        let path = find_path(&map, &config, start, GOAL).unwrap();
+        assert_eq!(path[1], IVec2::new(6, 9));
+    }
+
+    #[test]
+    fn gives_up_after_max_search_nodes() {
+        let config = PathConfig {
+            flying: true,
+            ..Default::default()
+        };
+        // a wall down the middle with a single gap at `gap_y`
+        let size = World::TEST_SIZE;
+        let wall_with_gap = |gap_y| {
+            let blocks: Vec<_> = (0..size)
+                .filter(|&y| y != gap_y)
+                .map(|y| (IVec2::new(64, y), BlockName::Dirt))
+                .collect();
+            World::with_blocks(&blocks)
+        };
+        let start = IVec2::new(1, 1);
+        let goal = IVec2::new(66, 1);
+
+        let near = wall_with_gap(2);
+        assert!(find_path(&near, &config, start, goal).is_some());
+        // still a way through, but finding it means searching most of
+        // the left half first
+        let far = wall_with_gap(size - 2);
+        assert_eq!(find_path(&far, &config, start, goal), None);
+    }
+}
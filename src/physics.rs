@@ -0,0 +1,168 @@
+use crate::world::World;
+use bitflags::bitflags;
+use glam::Vec2;
+use std::ops::RangeInclusive;
+
+pub const GRAVITY_FORCE: f32 = 30.0;
+
+// how close two edges have to be to count as touching, not overlapping
+const EPSILON: f32 = 1e-4;
+
+bitflags! {
+    /// Which sides of a collider were stopped by a block in the last move
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct Contacts: u8 {
+        const LEFT = 1 << 0;
+        const RIGHT = 1 << 1;
+        const TOP = 1 << 2;
+        const BOTTOM = 1 << 3;
+    }
+}
+
+impl Contacts {
+    /// Sum of the surface normals of every touched side.
+    /// Landing on the ground gives `(0, -1)`, since +y is down
+    #[must_use]
+    pub fn normal(&self) -> Vec2 {
+        let mut normal = Vec2::ZERO;
+        if self.contains(Contacts::LEFT) {
+            normal.x += 1.0;
+        }
+        if self.contains(Contacts::RIGHT) {
+            normal.x -= 1.0;
+        }
+        if self.contains(Contacts::TOP) {
+            normal.y += 1.0;
+        }
+        if self.contains(Contacts::BOTTOM) {
+            normal.y -= 1.0;
+        }
+        normal
+    }
+}
+
+fn is_solid(map: &World, x: i32, y: i32) -> bool {
+    map.get_block(x, y).is_some_and(|block| block.can_collide)
+}
+
+/// Tiles the span `min..max` overlaps. Only touching an edge doesn't count
+pub fn overlapping_tiles(min: f32, max: f32) -> RangeInclusive<i32> {
+    (min + EPSILON).floor() as i32..=(max - EPSILON).ceil() as i32 - 1
+}
+
+/// Do two boxes (top left, size) overlap. Touching edges don't count
+#[must_use]
+pub fn boxes_overlap(
+    a_pos: Vec2,
+    a_size: Vec2,
+    b_pos: Vec2,
+    b_size: Vec2,
+) -> bool {
+    a_pos.x < b_pos.x + b_size.x
+        && b_pos.x < a_pos.x + a_size.x
+        && a_pos.y < b_pos.y + b_size.y
+        && b_pos.y < a_pos.y + a_size.y
+}
+
+/// How far a box can move along `axis` (0 = x, 1 = y) before it hits a
+/// block, and whether it did. Only checks the tiles in the way, so it is
+/// exact for any distance, no sub-steps needed
+fn sweep_axis(
+    map: &World,
+    pos: Vec2,
+    size: Vec2,
+    delta: f32,
+    axis: usize,
+) -> (f32, bool) {
+    if delta == 0.0 {
+        return (0.0, false);
+    }
+    let cross_axis = 1 - axis;
+    let cross =
+        overlapping_tiles(pos[cross_axis], pos[cross_axis] + size[cross_axis]);
+    let line_is_solid = |line: i32| {
+        cross.clone().any(|other| {
+            if axis == 0 {
+                is_solid(map, line, other)
+            } else {
+                is_solid(map, other, line)
+            }
+        })
+    };
+
+    if delta > 0.0 {
+        let edge = pos[axis] + size[axis];
+        let first = (edge - EPSILON).ceil() as i32;
+        let last = (edge + delta - EPSILON).ceil() as i32 - 1;
+        for line in first..=last {
+            if line_is_solid(line) {
+                return ((line as f32 - edge).max(0.0), true);
+            }
+        }
+    } else {
+        let edge = pos[axis];
+        let first = (edge + EPSILON).floor() as i32 - 1;
+        let last = (edge + delta + EPSILON).floor() as i32;
+        for line in (last..=first).rev() {
+            if line_is_solid(line) {
+                return (((line + 1) as f32 - edge).min(0.0), true);
+            }
+        }
+    }
+    (delta, false)
+}
+
+/// Move a box of `size` at `pos` by `velocity` for one tick, stopping flush
+/// against (and zeroing velocity into) any block in the way.
+/// X moves first, then Y. Returns the sides that hit something
+pub fn move_and_collide(
+    map: &World,
+    pos: &mut Vec2,
+    velocity: &mut Vec2,
+    size: Vec2,
+    fps: f32,
+) -> Contacts {
+    let delta = *velocity / fps;
+    let mut contacts = Contacts::empty();
+
+    let (moved, hit) = sweep_axis(map, *pos, size, delta.x, 0);
+    pos.x += moved;
+    if hit {
+        contacts |= if delta.x > 0.0 {
+            Contacts::RIGHT
+        } else {
+            Contacts::LEFT
+        };
+        velocity.x = 0.0;
+    }
+
+    let (moved, hit) = sweep_axis(map, *pos, size, delta.y, 1);
+    pos.y += moved;
+    if hit {
+        contacts |= if delta.y > 0.0 {
+            Contacts::BOTTOM
+        } else {
+            Contacts::TOP
+        };
+        velocity.y = 0.0;
+    }
+
+    contacts
+}
+
+/// Slow horizontal movement down towards 0, never overshooting
+pub fn apply_friction(velocity: &mut Vec2, friction: f32, fps: f32) {
+    let friction = friction / fps; // units per second², tweak for slow down speed
+
+    if velocity.x > 0.0 {
+        velocity.x -= friction;
+        if velocity.x < 0.0 {
+            velocity.x = 0.0;
+        }
+    } else if velocity.x < 0.0 {
+        velocity.x += friction;
+        if velocity.x > 0.0 {
+            velocity.x = 0.0;
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{
+    components::{Collider, Gravity, Health, Position, Velocity},
+    damage::{Damage, Dead, deal_damage},
+    physics::{Contacts, boxes_overlap},
+};
+use glam::Vec2;
+use hecs::{Entity, Without, World as HecsWorld};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ProjectileKind {
+    #[default]
+    Arrow,
+    Spear,
+    MagicBolt,
+}
+
+/// What a projectile does when it flies into a block
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TileResponse {
+    Stick,
+    /// keep `restitution` of the speed, fizzle once out of bounces
+    Bounce {
+        restitution: f32,
+        bounces: u32,
+    },
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Projectile {
+    pub kind: ProjectileKind,
+    pub owner: Entity, // never hit by its own projectiles
+    pub damage: Damage,
+    pub knockback: f32, // along the direction it was flying
+    pub drag: f32,      // fraction of speed lost per second
+    pub on_tile: TileResponse,
+    pub stuck: bool, // stuck in a block, harmless until it despawns
+    pub age: u32,
+    pub lifetime: u32, // ticks until it despawns
+}
+
+/// Slow projectiles down with drag, and despawn the old ones
+pub fn projectile_flight_system(ecs: &mut HecsWorld, fps: f32) {
+    let mut expired = Vec::new();
+    for (entity, (projectile, velocity)) in
+        ecs.query_mut::<(&mut Projectile, &mut Velocity)>()
+    {
+        projectile.age += 1;
+        if projectile.age >= projectile.lifetime {
+            expired.push(entity);
+            continue;
+        }
+        if !projectile.stuck {
+            velocity.0 *= (1.0 - projectile.drag / fps).max(0.0);
+        }
+    }
+    for entity in expired {
+        let _ = ecs.despawn(entity);
+    }
+}
+
+/// Stick or bounce projectiles that hit a block this tick,
+/// then let flying ones hurt the first thing they touch
+pub fn projectile_collision_system(ecs: &mut HecsWorld) {
+    let mut stuck = Vec::new();
+    let mut spent = Vec::new();
+    for (entity, (projectile, velocity, collider)) in
+        ecs.query_mut::<(&mut Projectile, &mut Velocity, &Collider)>()
+    {
+        if projectile.stuck || collider.contacts.is_empty() {
+            continue;
+        }
+        match &mut projectile.on_tile {
+            TileResponse::Stick => {
+                projectile.stuck = true;
+                velocity.0 = Vec2::ZERO;
+                stuck.push(entity);
+            }
+            TileResponse::Bounce { bounces: 0, .. } => spent.push(entity),
+            TileResponse::Bounce {
+                restitution,
+                bounces,
+            } => {
+                // `impact` is the velocity the collision took away
+                let contacts = collider.contacts;
+                if contacts.intersects(Contacts::LEFT | Contacts::RIGHT) {
+                    velocity.0.x = -collider.impact.x * *restitution;
+                }
+                if contacts.intersects(Contacts::TOP | Contacts::BOTTOM) {
+                    velocity.0.y = -collider.impact.y * *restitution;
+                }
+                *bounces -= 1;
+            }
+        }
+    }
+    for entity in stuck {
+        let _ = ecs.remove_one::<Gravity>(entity);
+    }
+
+    let flying: Vec<_> = ecs
+        .query_mut::<(&Projectile, &Position, &Velocity, &Collider)>()
+        .into_iter()
+        .filter(|(_, (projectile, ..))| !projectile.stuck)
+        .map(|(entity, (projectile, pos, velocity, collider))| {
+            (entity, *projectile, pos.0, velocity.0, collider.size)
+        })
+        .collect();
+    let mut hits = Vec::new();
+    for (entity, projectile, pos, velocity, size) in flying {
+        let target = ecs
+            .query_mut::<Without<(&Position, &Collider), &Dead>>()
+            .without::<&Projectile>()
+            .with::<&Health>()
+            .into_iter()
+            .find(|(target, (target_pos, target_collider))| {
+                *target != projectile.owner
+                    && boxes_overlap(
+                        pos,
+                        size,
+                        target_pos.0,
+                        target_collider.size,
+                    )
+            })
+            .map(|(target, _)| target);
+        if let Some(target) = target {
+            let knockback = velocity.normalize_or_zero() * projectile.knockback;
+            hits.push((
+                target,
+                projectile
+                    .damage
+                    .with_knockback(knockback)
+                    .with_attacker(projectile.owner),
+            ));
+            spent.push(entity);
+        }
+    }
+
+    for (target, damage) in hits {
+        deal_damage(ecs, target, damage);
+    }
+    for entity in spent {
+        let _ = ecs.despawn(entity);
+    }
+}
@@ -0,0 +1,128 @@
+use glam::Vec2;
+use hecs::{Entity, World as HecsWorld};
+
+use crate::{
+    components::{
+        Collider, Gravity, Position, PreviousPosition, Sprite, Velocity,
+    },
+    damage::{Damage, DamageSource},
+    projectile::{Projectile, ProjectileKind, TileResponse},
+};
+
+/// Fire a projectile of `kind` from `owner`, centered on `center`
+pub fn spawn_projectile_from_kind(
+    ecs: &mut HecsWorld,
+    kind: ProjectileKind,
+    owner: Entity,
+    center: Vec2,
+    velocity: Vec2,
+) -> Entity {
+    match kind {
+        ProjectileKind::Arrow => spawn_arrow(ecs, owner, center, velocity),
+        ProjectileKind::Spear => spawn_spear(ecs, owner, center, velocity),
+        ProjectileKind::MagicBolt => {
+            spawn_magic_bolt(ecs, owner, center, velocity)
+        }
+    }
+}
+
+/// Light, flies far, sticks into blocks
+pub fn spawn_arrow(
+    ecs: &mut HecsWorld,
+    owner: Entity,
+    center: Vec2,
+    velocity: Vec2,
+) -> Entity {
+    const SIZE: Vec2 = Vec2 { x: 0.3, y: 0.3 };
+    let pos = center - SIZE / 2.0;
+    ecs.spawn((
+        Projectile {
+            kind: ProjectileKind::Arrow,
+            owner,
+            damage: Damage::new(12.0, DamageSource::Projectile),
+            knockback: 6.0,
+            drag: 0.1,
+            on_tile: TileResponse::Stick,
+            stuck: false,
+            age: 0,
+            lifetime: 60 * 10,
+        },
+        Position(pos),
+        PreviousPosition(pos),
+        Velocity(velocity),
+        Collider::new(SIZE),
+        Gravity {
+            scale: 0.5,
+            ..Default::default()
+        },
+        Sprite {
+            color: (200, 180, 140),
+        },
+    ))
+}
+
+/// Heavy and short ranged, but hits hard
+pub fn spawn_spear(
+    ecs: &mut HecsWorld,
+    owner: Entity,
+    center: Vec2,
+    velocity: Vec2,
+) -> Entity {
+    const SIZE: Vec2 = Vec2 { x: 0.4, y: 0.4 };
+    let pos = center - SIZE / 2.0;
+    ecs.spawn((
+        Projectile {
+            kind: ProjectileKind::Spear,
+            owner,
+            damage: Damage::new(25.0, DamageSource::Projectile),
+            knockback: 10.0,
+            drag: 0.05,
+            on_tile: TileResponse::Stick,
+            stuck: false,
+            age: 0,
+            lifetime: 60 * 15,
+        },
+        Position(pos),
+        PreviousPosition(pos),
+        Velocity(velocity),
+        Collider::new(SIZE),
+        Gravity::default(),
+        Sprite {
+            color: (128, 128, 128),
+        },
+    ))
+}
+
+/// Weightless, bounces off blocks a few times before fizzling out
+pub fn spawn_magic_bolt(
+    ecs: &mut HecsWorld,
+    owner: Entity,
+    center: Vec2,
+    velocity: Vec2,
+) -> Entity {
+    const SIZE: Vec2 = Vec2 { x: 0.3, y: 0.3 };
+    let pos = center - SIZE / 2.0;
+    ecs.spawn((
+        Projectile {
+            kind: ProjectileKind::MagicBolt,
+            owner,
+            damage: Damage::new(15.0, DamageSource::Projectile),
+            knockback: 4.0,
+            drag: 0.0,
+            on_tile: TileResponse::Bounce {
+                restitution: 0.8,
+                bounces: 3,
+            },
+            stuck: false,
+            age: 0,
+            lifetime: 60 * 3,
+        },
+        Position(pos),
+        PreviousPosition(pos),
+        Velocity(velocity),
+        Collider::new(SIZE),
+        Sprite {
+            color: (186, 85, 211),
+        },
+    ))
+}
@@ -1,4 +1,28 @@
+use rand::Rng;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Range {
     pub min: f32,
     pub max: f32,
 }
+
+impl Range {
+    #[must_use]
+    pub const fn new(min: f32, max: f32) -> Self {
+        Self { min, max }
+    }
+
+    /// A range that only contains `value`
+    #[must_use]
+    pub const fn exactly(value: f32) -> Self {
+        Self::new(value, value)
+    }
+
+    /// Pick a uniformly random value in `min..=max`
+    pub fn sample(&self, rng: &mut impl Rng) -> f32 {
+        if self.max <= self.min {
+            return self.min;
+        }
+        rng.random_range(self.min..=self.max)
+    }
+}
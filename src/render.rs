@@ -1,4 +1,5 @@
-use sdl2::{rect::FRect, render::Canvas, video::Window};
+use glam::Vec2;
+use sdl2::{rect::FRect, render::Canvas, ttf::Font, video::Window};
 
 #[derive(Clone, Copy)]
 pub struct Rect {
@@ -21,6 +22,13 @@ impl Rect {
         self.y + self.h
     }
 
+    /// Is `point` inside, counting the top and left edges but not the
+    /// bottom and right
+    pub fn contains(&self, point: Vec2) -> bool {
+        (self.x..self.max_x()).contains(&point.x)
+            && (self.y..self.max_y()).contains(&point.y)
+    }
+
     pub fn area(&self) -> f32 {
         self.w.max(0.0) * self.h.max(0.0)
     }
@@ -55,6 +63,31 @@ impl From<Rect> for FRect {
         Self::new(value.x, value.y, value.w, value.h)
     }
 }
+/// Draw `text` in `color`, with its top left corner at `pos`
+pub fn draw_text(
+    canvas: &mut Canvas<Window>,
+    font: &Font,
+    text: &str,
+    pos: Vec2,
+    color: (u8, u8, u8),
+) -> Result<(), Box<dyn std::error::Error>> {
+    // an empty string has no surface to render
+    if text.is_empty() {
+        return Ok(());
+    }
+    let surface = font.render(text).blended(color)?;
+    let texture_creator = canvas.texture_creator();
+    let texture = texture_creator.create_texture_from_surface(&surface)?;
+    let dst = FRect::new(
+        pos.x,
+        pos.y,
+        surface.width() as f32,
+        surface.height() as f32,
+    );
+    canvas.copy_f(&texture, None, dst)?;
+    Ok(())
+}
+
 /// Returns if the two rects overlap
 pub fn overlaps(a: Rect, b: Rect) -> bool {
     a.x <= b.max_x() && a.max_x() >= b.x && a.y <= b.max_y() && a.max_y() >= b.y
@@ -0,0 +1,227 @@
+use crate::{
+    equipment::{Equipment, EquipmentSlot},
+    experience::Experience,
+    inventory::Inventory,
+    item::ItemName,
+    items::item_from_name,
+    player::Player,
+    skill::SkillId,
+    stash::Stash,
+};
+use hecs::{Entity, World as HecsWorld};
+use std::{fmt::Display, fs, io, path::Path, str::FromStr};
+
+pub const SAVE_PATH: &str = "player.save";
+
+/// Everything about the player that is kept between sessions.
+/// Saved as plain text, one `key value...` per line
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PlayerSave {
+    pub experience: Experience,
+    pub skills: Vec<SkillId>,
+    pub stash: Stash,
+    pub inventory: Option<Inventory>, // `None` in saves from before it was kept
+    pub equipment: Equipment,
+}
+
+impl PlayerSave {
+    #[must_use]
+    pub fn from_player(
+        player: &Player,
+        inventory: &Inventory,
+        equipment: &Equipment,
+    ) -> Self {
+        Self {
+            experience: player.experience,
+            skills: player.skills.allocated().to_vec(),
+            stash: player.stash.clone(),
+            inventory: Some(inventory.clone()),
+            equipment: *equipment,
+        }
+    }
+
+    /// Overwrite `player`'s progress with this save. The inventory and
+    /// equipment are only replaced if the save has an inventory
+    pub fn apply(
+        &self,
+        player: &mut Player,
+        inventory: &mut Inventory,
+        equipment: &mut Equipment,
+    ) {
+        player.experience = self.experience;
+        player.skills = Default::default();
+        player.skills.restore(self.skills.iter().copied());
+        player.stash = self.stash.clone();
+        if let Some(saved) = &self.inventory {
+            *inventory = saved.clone();
+            *equipment = self.equipment;
+        }
+    }
+
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        let skills: Vec<String> =
+            self.skills.iter().map(ToString::to_string).collect();
+        let mut text = format!(
+            "level {}\nxp {}\nskill_points {}\nskills {}\n",
+            self.experience.level,
+            self.experience.xp,
+            self.experience.skill_points,
+            skills.join(" "),
+        );
+        // one line per entry, items are rebuilt from their name
+        for item in self.stash.items() {
+            text += &format!("stash {:?} {}\n", item.name, item.amount);
+        }
+        if let Some(inventory) = &self.inventory {
+            text += &format!("inventory_size {}\n", inventory.len());
+            for (index, item) in inventory.get_items().iter().enumerate() {
+                if let Some(item) = item {
+                    text += &format!(
+                        "inventory {index} {:?} {}\n",
+                        item.name, item.amount
+                    );
+                }
+            }
+        }
+        for (slot, item) in self.equipment.iter() {
+            if let Some(item) = item {
+                text +=
+                    &format!("equipment {} {:?}\n", slot.index(), item.name);
+            }
+        }
+        text
+    }
+
+    /// Lines this doesn't know about are skipped, so older builds can
+    /// read newer saves
+    pub fn parse(text: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut save = Self::default();
+        let mut inventory_size = None;
+        let mut inventory_items = Vec::new();
+        for line in text.lines() {
+            let mut words = line.split_whitespace();
+            let Some(key) = words.next() else {
+                continue;
+            };
+            match key {
+                "level" => save.experience.level = parse_one(key, words)?,
+                "xp" => save.experience.xp = parse_one(key, words)?,
+                "skill_points" => {
+                    save.experience.skill_points = parse_one(key, words)?;
+                }
+                "skills" => {
+                    save.skills = words
+                        .map(str::parse)
+                        .collect::<Result<_, _>>()
+                        .map_err(|e| format!("bad skill id: {e}"))?;
+                }
+                "stash" => {
+                    let name = parse_name(key, &mut words)?;
+                    let amount = parse_one(key, words)?;
+                    save.stash.deposit(item_from_name(name, amount));
+                }
+                "inventory_size" => {
+                    inventory_size = Some(parse_one(key, words)?);
+                }
+                "inventory" => {
+                    let index = parse_one(key, &mut words)?;
+                    let name = parse_name(key, &mut words)?;
+                    let amount = parse_one(key, words)?;
+                    inventory_items.push((index, item_from_name(name, amount)));
+                }
+                "equipment" => {
+                    let index: usize = parse_one(key, &mut words)?;
+                    let slot = *EquipmentSlot::ALL
+                        .get(index)
+                        .ok_or(format!("no equipment slot {index}"))?;
+                    let name = parse_name(key, words)?;
+                    save.equipment.equip(slot, item_from_name(name, 1))?;
+                }
+                _ => {}
+            }
+        }
+        if let Some(size) = inventory_size {
+            let mut inventory = Inventory::new(size);
+            for (index, item) in inventory_items {
+                if inventory.insert_at(index, item) > 0 {
+                    return Err(
+                        format!("inventory slot {index} is taken").into()
+                    );
+                }
+            }
+            save.inventory = Some(inventory);
+        }
+        // level 0 doesn't exist, and would make every level free
+        save.experience.level = save.experience.level.max(1);
+        Ok(save)
+    }
+}
+
+fn parse_name<'a>(
+    key: &str,
+    mut words: impl Iterator<Item = &'a str>,
+) -> Result<ItemName, Box<dyn std::error::Error>> {
+    let name = words.next().ok_or(format!("`{key}` has no item"))?;
+    Ok(ItemName::parse(name)
+        .ok_or(format!("unknown item `{name}` in `{key}`"))?)
+}
+
+fn parse_one<'a, T: FromStr>(
+    key: &str,
+    mut words: impl Iterator<Item = &'a str>,
+) -> Result<T, Box<dyn std::error::Error>>
+where
+    T::Err: Display,
+{
+    let word = words.next().ok_or(format!("`{key}` has no value"))?;
+    Ok(word
+        .parse()
+        .map_err(|e| format!("bad value for `{key}`: {e}"))?)
+}
+
+/// Write `player`'s progress to `path`
+pub fn save_player(
+    ecs: &HecsWorld,
+    player: Entity,
+    path: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut query =
+        ecs.query_one::<(&Player, &Inventory, &Equipment)>(player)?;
+    let (player, inventory, equipment) =
+        query.get().ok_or("the player is missing components")?;
+    let save = PlayerSave::from_player(player, inventory, equipment);
+    fs::write(path, save.to_text())?;
+    Ok(())
+}
+
+/// Read `player`'s progress back from `path`. Returns whether an
+/// inventory was restored, false if there was no save yet or it is too
+/// old to have one. A save that can't be read is copied to
+/// `<path>.bak` first, so saving over it later loses nothing
+pub fn load_player(
+    ecs: &mut HecsWorld,
+    player: Entity,
+    path: &Path,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e.into()),
+    };
+    let save = match PlayerSave::parse(&text) {
+        Ok(save) => save,
+        Err(e) => {
+            let mut backup = path.as_os_str().to_owned();
+            backup.push(".bak");
+            fs::copy(path, &backup)?;
+            return Err(e);
+        }
+    };
+    let (player, inventory, equipment) =
+        ecs.query_one_mut::<(&mut Player, &mut Inventory, &mut Equipment)>(
+            player,
+        )?;
+    save.apply(player, inventory, equipment);
+    Ok(save.inventory.is_some())
+}
@@ -0,0 +1,274 @@
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+/// When in a tick a system runs. Stages run in the order listed here
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Stage {
+    Input,
+    PrePhysics,
+    Physics,
+    PostPhysics,
+    World,
+    RenderPrep,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 6] = [
+        Stage::Input,
+        Stage::PrePhysics,
+        Stage::Physics,
+        Stage::PostPhysics,
+        Stage::World,
+        Stage::RenderPrep,
+    ];
+}
+
+pub type SystemFn<S> = fn(&mut S) -> Result<(), Box<dyn std::error::Error>>;
+
+pub struct System<S> {
+    pub name: &'static str,
+    pub stage: Stage,
+    pub enabled: bool,
+    pub last_duration: Duration, // how long the last run took
+    run: SystemFn<S>,
+    before: Vec<&'static str>,
+    after: Vec<&'static str>,
+}
+
+impl<S> System<S> {
+    #[must_use]
+    pub fn new(name: &'static str, stage: Stage, run: SystemFn<S>) -> Self {
+        Self {
+            name,
+            stage,
+            enabled: true,
+            last_duration: Duration::ZERO,
+            run,
+            before: Vec::new(),
+            after: Vec::new(),
+        }
+    }
+
+    /// Run this system before `other` (if they share a stage)
+    #[must_use]
+    pub fn before(mut self, other: &'static str) -> Self {
+        self.before.push(other);
+        self
+    }
+
+    /// Run this system after `other` (if they share a stage)
+    #[must_use]
+    pub fn after(mut self, other: &'static str) -> Self {
+        self.after.push(other);
+        self
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScheduleError {
+    DuplicateSystem(&'static str),
+    UnknownSystem(&'static str),
+    /// `before`/`after` asks for an order that the stages already rule out
+    WrongStage(&'static str, &'static str),
+    Cycle(Stage),
+}
+
+impl fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScheduleError::DuplicateSystem(name) => {
+                write!(f, "system `{name}` is registered twice")
+            }
+            ScheduleError::UnknownSystem(name) => {
+                write!(f, "ordering refers to unknown system `{name}`")
+            }
+            ScheduleError::WrongStage(a, b) => write!(
+                f,
+                "`{a}` can't run before `{b}`, it is in a later stage"
+            ),
+            ScheduleError::Cycle(stage) => {
+                write!(f, "systems in stage {stage:?} depend on each other")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScheduleError {}
+
+/// Runs registered systems once per tick, stage by stage,
+/// respecting `before`/`after` constraints inside each stage
+pub struct Scheduler<S> {
+    systems: Vec<System<S>>,
+    order: Option<Vec<usize>>, // `None` when systems changed since last sort
+}
+
+impl<S> Default for Scheduler<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> Scheduler<S> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            systems: Vec::new(),
+            order: None,
+        }
+    }
+
+    pub fn add_system(
+        &mut self,
+        system: System<S>,
+    ) -> Result<(), ScheduleError> {
+        if self.systems.iter().any(|s| s.name == system.name) {
+            return Err(ScheduleError::DuplicateSystem(system.name));
+        }
+        self.systems.push(system);
+        self.order = None;
+        Ok(())
+    }
+
+    /// Returns `false` if there is no system called `name`
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) -> bool {
+        match self.systems.iter_mut().find(|s| s.name == name) {
+            Some(system) => {
+                system.enabled = enabled;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the new state, or `None` if there is no system called `name`
+    pub fn toggle(&mut self, name: &str) -> Option<bool> {
+        let system = self.systems.iter_mut().find(|s| s.name == name)?;
+        system.enabled = !system.enabled;
+        Some(system.enabled)
+    }
+
+    /// Every system, in the order they run
+    pub fn systems(&self) -> Result<Vec<&System<S>>, ScheduleError> {
+        let order = match &self.order {
+            Some(order) => order.clone(),
+            None => self.sort()?,
+        };
+        Ok(order.into_iter().map(|i| &self.systems[i]).collect())
+    }
+
+    fn index_of(&self, name: &'static str) -> Result<usize, ScheduleError> {
+        self.systems
+            .iter()
+            .position(|s| s.name == name)
+            .ok_or(ScheduleError::UnknownSystem(name))
+    }
+
+    /// Stage order first, then a topological sort inside each stage.
+    /// Ties keep registration order, so unconstrained systems are stable
+    fn sort(&self) -> Result<Vec<usize>, ScheduleError> {
+        // edges[a] contains b if a has to run before b
+        let mut edges: Vec<Vec<usize>> = vec![Vec::new(); self.systems.len()];
+        for (i, system) in self.systems.iter().enumerate() {
+            for &other in &system.before {
+                edges[i].push(self.index_of(other)?);
+            }
+            for &other in &system.after {
+                edges[self.index_of(other)?].push(i);
+            }
+        }
+
+        // across stages the stage order wins, so only check it agrees
+        let mut incoming = vec![0usize; self.systems.len()];
+        for (from, targets) in edges.iter().enumerate() {
+            for &to in targets {
+                let (a, b) = (&self.systems[from], &self.systems[to]);
+                if a.stage > b.stage {
+                    return Err(ScheduleError::WrongStage(a.name, b.name));
+                }
+                if a.stage == b.stage {
+                    incoming[to] += 1;
+                }
+            }
+        }
+
+        let mut order = Vec::with_capacity(self.systems.len());
+        let mut done = vec![false; self.systems.len()];
+        for stage in Stage::ALL {
+            let in_stage: Vec<usize> = (0..self.systems.len())
+                .filter(|&i| self.systems[i].stage == stage)
+                .collect();
+            for _ in 0..in_stage.len() {
+                let next = in_stage
+                    .iter()
+                    .copied()
+                    .find(|&i| !done[i] && incoming[i] == 0)
+                    .ok_or(ScheduleError::Cycle(stage))?;
+                done[next] = true;
+                for &to in &edges[next] {
+                    if self.systems[to].stage == stage {
+                        incoming[to] -= 1;
+                    }
+                }
+                order.push(next);
+            }
+        }
+        Ok(order)
+    }
+
+    /// Run every enabled system once, timing each one.
+    /// A failing system doesn't stop the rest; the first error is returned
+    pub fn run(
+        &mut self,
+        state: &mut S,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if self.order.is_none() {
+            self.order = Some(self.sort()?);
+        }
+        let order = self.order.as_ref().unwrap(); // just set above
+
+        let mut first_error = None;
+        for &i in order {
+            let system = &mut self.systems[i];
+            if !system.enabled {
+                system.last_duration = Duration::ZERO;
+                continue;
+            }
+            let start = Instant::now();
+            let result = (system.run)(state);
+            system.last_duration = start.elapsed();
+
+            if let Err(e) = result
+                && first_error.is_none()
+            {
+                first_error = Some(format!("{}: {}", system.name, e).into());
+            }
+        }
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// One line per system with its stage and last run time
+    #[must_use]
+    pub fn timing_report(&self) -> String {
+        let Ok(systems) = self.systems() else {
+            return String::from("schedule is invalid");
+        };
+        systems
+            .iter()
+            .map(|s| {
+                format!(
+                    "{:<12} {:<24} {:>10.3?}{}",
+                    format!("{:?}", s.stage),
+                    s.name,
+                    s.last_duration,
+                    if s.enabled { "" } else { " (disabled)" }
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
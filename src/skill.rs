@@ -0,0 +1,258 @@
+use crate::{
+    components::{Collider, Velocity},
+    experience::Experience,
+    skills::skill_from_id,
+    stats::StatModifier,
+    utils::Cooldown,
+};
+use sdl2::keyboard::Keycode;
+use std::fmt;
+
+pub type SkillId = u32;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AbilityName {
+    Dash,
+    GroundSlam,
+}
+
+/// An ability a skill unlocks, used with its key
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ability {
+    pub name: AbilityName,
+    pub key: Keycode,
+    pub cooldown: f32, // seconds
+}
+
+impl Ability {
+    #[must_use]
+    pub fn cooldown_ticks(&self, tick_rate: f32) -> u32 {
+        (self.cooldown * tick_rate).round() as u32
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SkillGrant {
+    /// always on once allocated
+    Passive(&'static [StatModifier]),
+    Active(Ability),
+}
+
+/// One node of the skill tree
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SkillNode {
+    pub id: SkillId, // saved, so never reuse one
+    pub name: &'static str,
+    pub cost: u32,                    // skill points
+    pub requires: &'static [SkillId], // all of these first
+    pub grant: SkillGrant,
+    pub pos: (u32, u32), // column and row in the skill panel
+    pub color: (u8, u8, u8),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SkillError {
+    UnknownSkill(SkillId),
+    AlreadyAllocated(SkillId),
+    MissingPrerequisite(SkillId),
+    NotEnoughPoints { cost: u32, available: u32 },
+}
+
+impl fmt::Display for SkillError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkillError::UnknownSkill(id) => write!(f, "no skill with id {id}"),
+            SkillError::AlreadyAllocated(id) => {
+                write!(f, "skill {id} is already allocated")
+            }
+            SkillError::MissingPrerequisite(id) => {
+                write!(f, "skill {id} has to be allocated first")
+            }
+            SkillError::NotEnoughPoints { cost, available } => write!(
+                f,
+                "needs {cost} skill points, only {available} available"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SkillError {}
+
+/// An ability that is still playing out
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AbilityState {
+    Dashing {
+        ticks_left: u32,
+    },
+    /// falling fast, hits everything around when it lands
+    Slamming,
+}
+
+/// The skills the player has allocated, and the state of their abilities
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Skills {
+    allocated: Vec<SkillId>,
+    cooldowns: Vec<(AbilityName, Cooldown)>,
+    pub active: Option<AbilityState>,
+}
+
+impl Skills {
+    const DASH_SPEED: f32 = 30.0;
+    const DASH_TICKS: u32 = 8;
+    const SLAM_SPEED: f32 = 24.0; // just under what hurts to land at
+
+    #[must_use]
+    pub fn has(&self, id: SkillId) -> bool {
+        self.allocated.contains(&id)
+    }
+
+    #[must_use]
+    pub fn allocated(&self) -> &[SkillId] {
+        &self.allocated
+    }
+
+    /// Returns why `id` can't be allocated with `points` to spend, if it can't
+    pub fn check(&self, id: SkillId, points: u32) -> Result<(), SkillError> {
+        let node = skill_from_id(id).ok_or(SkillError::UnknownSkill(id))?;
+        if self.has(id) {
+            return Err(SkillError::AlreadyAllocated(id));
+        }
+        if let Some(&missing) =
+            node.requires.iter().find(|&&required| !self.has(required))
+        {
+            return Err(SkillError::MissingPrerequisite(missing));
+        }
+        if node.cost > points {
+            return Err(SkillError::NotEnoughPoints {
+                cost: node.cost,
+                available: points,
+            });
+        }
+        Ok(())
+    }
+
+    /// Spend `experience`'s skill points on `id`
+    pub fn allocate(
+        &mut self,
+        experience: &mut Experience,
+        id: SkillId,
+    ) -> Result<(), SkillError> {
+        self.check(id, experience.skill_points)?;
+        let node = skill_from_id(id).ok_or(SkillError::UnknownSkill(id))?;
+        experience.skill_points -= node.cost;
+        self.allocated.push(id);
+        Ok(())
+    }
+
+    /// Allocate `ids` without spending points, skipping any that no longer
+    /// exist. For loading a save
+    pub fn restore(&mut self, ids: impl IntoIterator<Item = SkillId>) {
+        for id in ids {
+            if skill_from_id(id).is_some() && !self.has(id) {
+                self.allocated.push(id);
+            }
+        }
+    }
+
+    /// Take back every skill, refunding the points into `experience`
+    pub fn reset(&mut self, experience: &mut Experience) {
+        let refund: u32 = self
+            .allocated
+            .drain(..)
+            .filter_map(skill_from_id)
+            .map(|node| node.cost)
+            .sum();
+        experience.skill_points += refund;
+        self.cooldowns.clear();
+        self.active = None;
+    }
+
+    /// Every allocated node, in the order they were allocated
+    pub fn nodes(&self) -> impl Iterator<Item = &'static SkillNode> {
+        self.allocated.iter().filter_map(|&id| skill_from_id(id))
+    }
+
+    pub fn abilities(&self) -> impl Iterator<Item = Ability> {
+        self.nodes().filter_map(|node| match node.grant {
+            SkillGrant::Active(ability) => Some(ability),
+            SkillGrant::Passive(_) => None,
+        })
+    }
+
+    #[must_use]
+    pub fn is_ready(&self, ability: AbilityName, tick: u64) -> bool {
+        self.cooldowns
+            .iter()
+            .find(|(name, _)| *name == ability)
+            .is_none_or(|(_, cooldown)| cooldown.is_ready(tick))
+    }
+
+    /// `ability` was used on `tick`, wait its cooldown before the next use
+    pub fn put_on_cooldown(
+        &mut self,
+        ability: &Ability,
+        tick: u64,
+        tick_rate: f32,
+    ) {
+        let cooldown = Cooldown {
+            last_tick: tick,
+            delay: ability.cooldown_ticks(tick_rate),
+        };
+        self.cooldowns.retain(|(name, _)| *name != ability.name);
+        self.cooldowns.push((ability.name, cooldown));
+    }
+
+    /// Start `ability`. `dir_x` is which way a dash goes.
+    /// Returns if it could be used at all, ground slam needs to be airborne
+    pub fn start(
+        &mut self,
+        ability: AbilityName,
+        velocity: &mut Velocity,
+        collider: &Collider,
+        dir_x: f32,
+    ) -> bool {
+        match ability {
+            AbilityName::Dash => {
+                velocity.0.x = dir_x.signum() * Self::DASH_SPEED;
+                velocity.0.y = velocity.0.y.min(0.0);
+                self.active = Some(AbilityState::Dashing {
+                    ticks_left: Self::DASH_TICKS,
+                });
+            }
+            AbilityName::GroundSlam => {
+                if collider.on_ground() {
+                    return false;
+                }
+                velocity.0.x = 0.0;
+                velocity.0.y = velocity.0.y.max(Self::SLAM_SPEED);
+                self.active = Some(AbilityState::Slamming);
+            }
+        }
+        true
+    }
+
+    /// Count the active ability down. Returns true on the tick a
+    /// ground slam lands
+    pub fn update(&mut self, collider: &Collider) -> bool {
+        match self.active {
+            Some(AbilityState::Dashing { ticks_left }) => {
+                self.active = ticks_left
+                    .checked_sub(1)
+                    .filter(|&ticks_left| ticks_left > 0)
+                    .map(|ticks_left| AbilityState::Dashing { ticks_left });
+                false
+            }
+            Some(AbilityState::Slamming) if collider.on_ground() => {
+                self.active = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// While dashing, walking doesn't steer
+    #[must_use]
+    pub fn is_dashing(&self) -> bool {
+        matches!(self.active, Some(AbilityState::Dashing { .. }))
+    }
+}
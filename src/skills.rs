@@ -0,0 +1,107 @@
+use sdl2::keyboard::Keycode;
+
+use crate::{
+    skill::{Ability, AbilityName, SkillGrant, SkillId, SkillNode},
+    stats::{StatModifier, StatName},
+};
+
+pub const SKILL_SWIFT_FEET: SkillId = 1;
+pub const SKILL_STRONG_ARMS: SkillId = 2;
+pub const SKILL_PROSPECTOR: SkillId = 3;
+pub const SKILL_DASH: SkillId = 4;
+pub const SKILL_SPRING_STEP: SkillId = 5;
+pub const SKILL_LONG_ARMS: SkillId = 6;
+pub const SKILL_GROUND_SLAM: SkillId = 7;
+
+/// Every skill there is. Allocation is saved by id, so ids must stay
+/// the same even if nodes move around
+pub const SKILL_TREE: &[SkillNode] = &[
+    SkillNode {
+        id: SKILL_SWIFT_FEET,
+        name: "Swift Feet",
+        cost: 1,
+        requires: &[],
+        grant: SkillGrant::Passive(&[StatModifier::add(
+            StatName::MoveSpeed,
+            0.1,
+        )]),
+        pos: (0, 0),
+        color: (90, 180, 255),
+    },
+    SkillNode {
+        id: SKILL_STRONG_ARMS,
+        name: "Strong Arms",
+        cost: 1,
+        requires: &[],
+        grant: SkillGrant::Passive(&[StatModifier::add(
+            StatName::Damage,
+            0.15,
+        )]),
+        pos: (2, 0),
+        color: (220, 70, 70),
+    },
+    SkillNode {
+        id: SKILL_PROSPECTOR,
+        name: "Prospector",
+        cost: 1,
+        requires: &[],
+        grant: SkillGrant::Passive(&[StatModifier::add(
+            StatName::MiningSpeed,
+            0.2,
+        )]),
+        pos: (4, 0),
+        color: (230, 190, 60),
+    },
+    SkillNode {
+        id: SKILL_DASH,
+        name: "Dash",
+        cost: 2,
+        requires: &[SKILL_SWIFT_FEET],
+        grant: SkillGrant::Active(Ability {
+            name: AbilityName::Dash,
+            key: Keycode::LShift,
+            cooldown: 1.5,
+        }),
+        pos: (0, 1),
+        color: (140, 220, 255),
+    },
+    SkillNode {
+        id: SKILL_SPRING_STEP,
+        name: "Spring Step",
+        cost: 1,
+        requires: &[SKILL_SWIFT_FEET],
+        grant: SkillGrant::Passive(&[StatModifier::add(
+            StatName::JumpHeight,
+            0.25,
+        )]),
+        pos: (1, 1),
+        color: (120, 230, 160),
+    },
+    SkillNode {
+        id: SKILL_LONG_ARMS,
+        name: "Long Arms",
+        cost: 2,
+        requires: &[SKILL_STRONG_ARMS, SKILL_PROSPECTOR],
+        grant: SkillGrant::Passive(&[StatModifier::add(StatName::Reach, 0.2)]),
+        pos: (3, 1),
+        color: (200, 140, 230),
+    },
+    SkillNode {
+        id: SKILL_GROUND_SLAM,
+        name: "Ground Slam",
+        cost: 3,
+        requires: &[SKILL_STRONG_ARMS, SKILL_SPRING_STEP],
+        grant: SkillGrant::Active(Ability {
+            name: AbilityName::GroundSlam,
+            key: Keycode::E,
+            cooldown: 4.0,
+        }),
+        pos: (1, 2),
+        color: (170, 90, 40),
+    },
+];
+
+#[must_use]
+pub fn skill_from_id(id: SkillId) -> Option<&'static SkillNode> {
+    SKILL_TREE.iter().find(|node| node.id == id)
+}
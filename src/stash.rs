@@ -0,0 +1,163 @@
+use crate::{
+    block::BlockName,
+    inventory::Inventory,
+    item::{Item, ItemName},
+    physics::overlapping_tiles,
+    utils::can_stack,
+    world::World,
+};
+use glam::Vec2;
+use std::cmp::Reverse;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StashSort {
+    #[default]
+    Name,
+    Amount, // most first
+    Type,
+}
+
+impl StashSort {
+    /// The one after this, for cycling through them
+    #[must_use]
+    pub fn next(self) -> Self {
+        match self {
+            StashSort::Name => StashSort::Amount,
+            StashSort::Amount => StashSort::Type,
+            StashSort::Type => StashSort::Name,
+        }
+    }
+}
+
+/// The player's bank. One entry per kind of item, with no stack limit
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Stash {
+    items: Vec<Item>,
+}
+
+impl Stash {
+    /// Blocks from the edge of the player to a stash block
+    pub const REACH: f32 = 3.0;
+
+    #[must_use]
+    pub fn items(&self) -> &[Item] {
+        &self.items
+    }
+
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<&Item> {
+        self.items.get(index)
+    }
+
+    /// How many of `name` are stashed
+    #[must_use]
+    pub fn count(&self, name: ItemName) -> usize {
+        self.items
+            .iter()
+            .filter(|item| item.name == name)
+            .map(|item| item.amount)
+            .sum()
+    }
+
+    /// Put all of `item` in, ignoring its max stack
+    pub fn deposit(&mut self, item: Item) {
+        if item.amount == 0 {
+            return;
+        }
+        match self.items.iter_mut().find(|s| can_stack(s, &item)) {
+            Some(stashed) => stashed.amount += item.amount,
+            None => self.items.push(item),
+        }
+    }
+
+    /// Move every `name` out of `inventory` into the stash.
+    /// Returns how many were moved
+    pub fn deposit_all(
+        &mut self,
+        inventory: &mut Inventory,
+        name: ItemName,
+    ) -> usize {
+        let mut moved = 0;
+        for index in 0..inventory.len() {
+            if inventory
+                .get_item(index)
+                .is_some_and(|item| item.name == name)
+                && let Some(item) = inventory.take_item(index)
+            {
+                moved += item.amount;
+                self.deposit(item);
+            }
+        }
+        moved
+    }
+
+    /// Move up to `amount` of the entry at `index` into `inventory`, as
+    /// much as fits. Returns how many were moved
+    pub fn withdraw(
+        &mut self,
+        index: usize,
+        amount: usize,
+        inventory: &mut Inventory,
+    ) -> usize {
+        let Some(stashed) = self.items.get_mut(index) else {
+            return 0;
+        };
+        let mut taken = *stashed;
+        taken.amount = amount.min(stashed.amount);
+        let left_over = inventory.add_item(taken);
+        let moved = taken.amount - left_over;
+        stashed.amount -= moved;
+        if stashed.amount == 0 {
+            self.items.remove(index);
+        }
+        moved
+    }
+
+    /// Indices of every entry whose name contains `query`, ignoring case
+    /// and spaces. An empty query matches everything
+    #[must_use]
+    pub fn search(&self, query: &str) -> Vec<usize> {
+        let query: String = query
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+            .to_lowercase();
+        self.items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| {
+                format!("{:?}", item.name).to_lowercase().contains(&query)
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    pub fn sort(&mut self, by: StashSort) {
+        let name = |item: &Item| format!("{:?}", item.name);
+        match by {
+            StashSort::Name => self.items.sort_by_key(name),
+            StashSort::Amount => {
+                self.items.sort_by_key(|item| Reverse(item.amount));
+            }
+            StashSort::Type => self.items.sort_by(|a, b| {
+                format!("{:?}", a.item_type)
+                    .cmp(&format!("{:?}", b.item_type))
+                    .then_with(|| name(a).cmp(&name(b)))
+            }),
+        }
+    }
+}
+
+/// Is there a stash block within `Stash::REACH` of a box at `pos` (top
+/// left) of `size`
+#[must_use]
+pub fn stash_in_reach(map: &World, pos: Vec2, size: Vec2) -> bool {
+    let min = pos - Stash::REACH;
+    let max = pos + size + Stash::REACH;
+    overlapping_tiles(min.y, max.y).any(|y| {
+        overlapping_tiles(min.x, max.x).any(|x| {
+            map.get_block(x, y)
+                .is_some_and(|block| block.block_type == BlockName::Stash)
+        })
+    })
+}
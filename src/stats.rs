@@ -0,0 +1,247 @@
+use crate::{
+    effect::{EffectName, StatusEffects},
+    equipment::Equipment,
+    inventory::Inventory,
+    item::Item,
+    player::Player,
+    skill::{SkillGrant, SkillId},
+};
+use hecs::World as HecsWorld;
+
+/// Every stat but defense is a scale, 1 is normal
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StatName {
+    MiningSpeed, // block damage
+    MoveSpeed,   // max speed and acceleration
+    JumpHeight,  // how high a jump goes
+    Reach,       // mining distance and weapon reach
+    Damage,      // weapon and projectile damage
+    Defense,     // flat, 0 is none, see `Stats::damage_taken`
+    FallDamage,  // damage taken from landing, 0 is immune
+}
+
+impl StatName {
+    pub const ALL: [StatName; 7] = [
+        StatName::MiningSpeed,
+        StatName::MoveSpeed,
+        StatName::JumpHeight,
+        StatName::Reach,
+        StatName::Damage,
+        StatName::Defense,
+        StatName::FallDamage,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    /// The base value when nothing changed it
+    #[must_use]
+    pub fn default_base(self) -> f32 {
+        match self {
+            StatName::Defense => 0.0,
+            _ => 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ModifierKind {
+    /// added to the base, before any multipliers
+    Add,
+    /// multiplies the total
+    Multiply,
+}
+
+/// Where a modifier came from, so it can be taken away again
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ModifierSource {
+    HeldItem,
+    Equipment(usize), // slot index
+    Effect(EffectName),
+    Skill(SkillId),
+}
+
+/// A modifier as an item (or anything else) defines it, without a source
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StatModifier {
+    pub stat: StatName,
+    pub kind: ModifierKind,
+    pub value: f32,
+}
+
+impl StatModifier {
+    #[must_use]
+    pub const fn add(stat: StatName, value: f32) -> Self {
+        Self {
+            stat,
+            kind: ModifierKind::Add,
+            value,
+        }
+    }
+
+    #[must_use]
+    pub const fn multiply(stat: StatName, value: f32) -> Self {
+        Self {
+            stat,
+            kind: ModifierKind::Multiply,
+            value,
+        }
+    }
+}
+
+/// Base stats plus every modifier currently applied.
+/// A stat is `(base + adds) * multipliers`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stats {
+    base: [f32; StatName::ALL.len()],
+    modifiers: Vec<(ModifierSource, StatModifier)>,
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self {
+            base: StatName::ALL.map(StatName::default_base),
+            modifiers: Vec::new(),
+        }
+    }
+}
+
+impl Stats {
+    #[must_use]
+    pub fn with_base(mut self, stat: StatName, value: f32) -> Self {
+        self.base[stat.index()] = value;
+        self
+    }
+
+    #[must_use]
+    pub fn base(&self, stat: StatName) -> f32 {
+        self.base[stat.index()]
+    }
+
+    /// The final value of `stat`, never below 0
+    #[must_use]
+    pub fn get(&self, stat: StatName) -> f32 {
+        let mut add = 0.0;
+        let mut mul = 1.0;
+        for (_, modifier) in &self.modifiers {
+            if modifier.stat != stat {
+                continue;
+            }
+            match modifier.kind {
+                ModifierKind::Add => add += modifier.value,
+                ModifierKind::Multiply => mul *= modifier.value,
+            }
+        }
+        ((self.base(stat) + add) * mul).max(0.0)
+    }
+
+    pub fn add_modifier(
+        &mut self,
+        source: ModifierSource,
+        modifier: StatModifier,
+    ) {
+        self.modifiers.push((source, modifier));
+    }
+
+    /// Take away everything `source` added
+    pub fn remove_source(&mut self, source: ModifierSource) {
+        self.modifiers.retain(|(s, _)| *s != source);
+    }
+
+    /// Replace everything `source` added with `modifiers`
+    pub fn set_source(
+        &mut self,
+        source: ModifierSource,
+        modifiers: impl IntoIterator<Item = StatModifier>,
+    ) {
+        self.remove_source(source);
+        for modifier in modifiers {
+            self.add_modifier(source, modifier);
+        }
+    }
+
+    #[must_use]
+    pub fn has_source(&self, source: ModifierSource) -> bool {
+        self.modifiers.iter().any(|(s, _)| *s == source)
+    }
+
+    /// How much of a hit of `amount` gets through our defense.
+    /// `DEFENSE_HALVING` defense halves it, three times that quarters it
+    #[must_use]
+    pub fn damage_taken(&self, amount: f32) -> f32 {
+        const DEFENSE_HALVING: f32 = 10.0;
+        let defense = self.get(StatName::Defense);
+        amount * DEFENSE_HALVING / (DEFENSE_HALVING + defense)
+    }
+}
+
+/// The stat an effect changes, and by how much
+fn effect_modifier(name: EffectName, strength: f32) -> Option<StatModifier> {
+    match name {
+        EffectName::Speed => {
+            Some(StatModifier::add(StatName::MoveSpeed, strength))
+        }
+        EffectName::MiningHaste => {
+            Some(StatModifier::add(StatName::MiningSpeed, strength))
+        }
+        EffectName::FeatherFall => {
+            Some(StatModifier::add(StatName::FallDamage, -strength))
+        }
+        EffectName::Poison | EffectName::Regeneration => None,
+    }
+}
+
+/// Bring the modifiers from effects, equipment, the held item and skills
+/// up to date
+pub fn sync_stats_system(ecs: &mut HecsWorld) {
+    for (_, (stats, effects)) in ecs.query_mut::<(&mut Stats, &StatusEffects)>()
+    {
+        // effects that ran out since last tick
+        stats.modifiers.retain(|(source, _)| match source {
+            ModifierSource::Effect(name) => effects.get(*name).is_some(),
+            _ => true,
+        });
+        for effect in &effects.0 {
+            let modifier =
+                effect_modifier(effect.name, effect.total_strength());
+            stats.set_source(ModifierSource::Effect(effect.name), modifier);
+        }
+    }
+
+    for (_, (stats, equipment)) in ecs.query_mut::<(&mut Stats, &Equipment)>() {
+        for (slot, item) in equipment.iter() {
+            let modifiers = item.map_or(&[][..], |item| item.modifiers);
+            let defense = item.and_then(Item::armor_props).map(|armor| {
+                StatModifier::add(StatName::Defense, armor.defense)
+            });
+            stats.set_source(
+                ModifierSource::Equipment(slot.index()),
+                modifiers.iter().copied().chain(defense),
+            );
+        }
+    }
+
+    for (_, (stats, player, inventory)) in
+        ecs.query_mut::<(&mut Stats, &Player, &Inventory)>()
+    {
+        let modifiers = player
+            .held_item(inventory)
+            .map_or(&[][..], |item| item.modifiers);
+        stats.set_source(ModifierSource::HeldItem, modifiers.iter().copied());
+
+        // skills that were reset since last tick
+        stats.modifiers.retain(|(source, _)| match source {
+            ModifierSource::Skill(id) => player.skills.has(*id),
+            _ => true,
+        });
+        for node in player.skills.nodes() {
+            if let SkillGrant::Passive(modifiers) = node.grant {
+                stats.set_source(
+                    ModifierSource::Skill(node.id),
+                    modifiers.iter().copied(),
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{
+    camera::Camera,
+    components::{
+        Collider, Gravity, Health, Position, PreviousPosition, Sprite, Velocity,
+    },
+    damage::Dead,
+    enemy::Asleep,
+    physics::{GRAVITY_FORCE, move_and_collide},
+    player::Player,
+    world::World,
+};
+use glam::Vec2;
+use hecs::{Entity, Without, World as HecsWorld};
+use sdl2::{rect::FRect, render::Canvas, video::Window};
+
+pub fn gravity_system(ecs: &mut HecsWorld, fps: f32) {
+    for (_, (velocity, gravity)) in
+        ecs.query_mut::<Without<(&mut Velocity, &Gravity), &Asleep>>()
+    {
+        velocity.0.y = (velocity.0.y + GRAVITY_FORCE * gravity.scale / fps)
+            .min(gravity.terminal_velocity);
+    }
+}
+
+pub fn movement_system(ecs: &mut HecsWorld, map: &World, fps: f32) {
+    for (_, (pos, velocity, collider)) in
+        ecs.query_mut::<Without<
+            (&mut Position, &mut Velocity, &mut Collider),
+            &Asleep,
+        >>()
+    {
+        let before = velocity.0;
+        collider.contacts = move_and_collide(
+            map,
+            &mut pos.0,
+            &mut velocity.0,
+            collider.size,
+            fps,
+        );
+        collider.impact = before - velocity.0;
+    }
+
+    // no collider, nothing to stop it
+    for (_, (pos, velocity)) in ecs
+        .query_mut::<Without<(&mut Position, &Velocity), &Collider>>()
+        .without::<&Asleep>()
+    {
+        pos.0 += velocity.0 / fps;
+    }
+}
+
+/// Despawn everything that ran out of health, except the player
+pub fn death_system(ecs: &mut HecsWorld) {
+    let dead: Vec<Entity> = ecs
+        .query_mut::<Without<&Health, &Player>>()
+        .into_iter()
+        .filter(|(_, health)| health.is_dead())
+        .map(|(entity, _)| entity)
+        .collect();
+    for entity in dead {
+        let _ = ecs.despawn(entity);
+    }
+}
+
+/// Where `entity` should be drawn, `alpha` of the way through this tick
+#[must_use]
+pub fn interpolated_position(
+    ecs: &HecsWorld,
+    entity: Entity,
+    alpha: f32,
+) -> Option<Vec2> {
+    let mut query = ecs
+        .query_one::<(&Position, Option<&PreviousPosition>)>(entity)
+        .ok()?;
+    let (pos, previous) = query.get()?;
+    Some(lerp_position(pos, previous, alpha))
+}
+
+fn lerp_position(
+    pos: &Position,
+    previous: Option<&PreviousPosition>,
+    alpha: f32,
+) -> Vec2 {
+    match previous {
+        Some(previous) => previous.0.lerp(pos.0, alpha),
+        None => pos.0,
+    }
+}
+
+pub fn render_system(
+    ecs: &HecsWorld,
+    canvas: &mut Canvas<Window>,
+    camera: &Camera,
+    alpha: f32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for (_, (pos, previous, collider, sprite)) in ecs
+        .query::<Without<
+            (&Position, Option<&PreviousPosition>, &Collider, &Sprite),
+            &Dead,
+        >>()
+        .iter()
+    {
+        canvas.set_draw_color(sprite.color);
+
+        let pos = lerp_position(pos, previous, alpha);
+        let screen_pos = camera.global_to_screen(pos);
+        let screen_dims = camera.scale_global_to_screen(collider.size);
+
+        let rect = FRect::new(
+            screen_pos.x,
+            screen_pos.y,
+            screen_dims.x,
+            screen_dims.y,
+        );
+        // if we want not subpixel-perfect rendering, then use
+        // let rect = FRect::new(
+        //     screen_pos.x.round(),
+        //     screen_pos.y.round(),
+        //     screen_dims.x.round(),
+        //     screen_dims.y.round(),
+        // );
+
+        canvas.fill_frect(rect)?;
+    }
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use crate::item::Item;
+use glam::IVec2;
 
 #[derive(Clone, Copy, Debug, Default)]
 pub enum Direction {
@@ -10,6 +11,51 @@ pub enum Direction {
     None,
 }
 
+impl Direction {
+    /// Unit step in this direction, in world space (+y is down)
+    #[must_use]
+    pub fn as_ivec2(&self) -> IVec2 {
+        match self {
+            Direction::Up => IVec2::NEG_Y,
+            Direction::Down => IVec2::Y,
+            Direction::Left => IVec2::NEG_X,
+            Direction::Right => IVec2::X,
+            Direction::None => IVec2::ZERO,
+        }
+    }
+}
+
+/// Tick based delay between uses of something, like mining or swinging
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Cooldown {
+    pub last_tick: u64, // when it was last used
+    pub delay: u32,     // ticks between uses
+}
+
+impl Cooldown {
+    #[must_use]
+    pub fn new(delay: u32) -> Self {
+        Self {
+            last_tick: 0,
+            delay,
+        }
+    }
+
+    #[must_use]
+    pub fn is_ready(&self, tick: u64) -> bool {
+        self.last_tick + u64::from(self.delay) <= tick
+    }
+
+    /// Use it if it is ready. Returns if it was used
+    pub fn try_use(&mut self, tick: u64) -> bool {
+        if !self.is_ready(tick) {
+            return false;
+        }
+        self.last_tick = tick;
+        true
+    }
+}
+
 pub fn can_stack(a: &Item, b: &Item) -> bool {
     a.name == b.name && a.rarity == b.rarity && a.props == b.props
 }
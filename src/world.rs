@@ -1,11 +1,14 @@
 use crate::{
-    block::Block,
-    blocks::{block_air, block_dirt, block_stone},
-    items::item_from_name,
+    block::{Block, BlockName},
+    blocks::{block_air, block_dirt, block_lava, block_sludge, block_stone},
+    item::Item,
+    physics::overlapping_tiles,
     player::Player,
+    stats::Stats,
 };
-use glam::IVec2;
+use glam::{IVec2, Vec2};
 use noise::{NoiseFn, Perlin};
+use rand::{Rng, SeedableRng, rngs::StdRng};
 use std::{array, collections::HashMap};
 
 #[derive(Clone)]
@@ -15,11 +18,29 @@ pub struct Chunk {
     pub y: i32,
 }
 
-#[derive(Default)]
+/// What came out of a hit: every item dropped (and where), and the XP
+/// for every block that broke
+#[derive(Clone, Debug, Default)]
+pub struct Mined {
+    pub drops: Vec<(IVec2, Item)>,
+    pub xp: u32,
+}
+
+const LOOT_SEED_SALT: u64 = 0x6c6f_6f74; // "loot"
+
 pub struct World {
     chunks: HashMap<IVec2, Chunk>,
     perlin: Perlin,
     active_chunks: Vec<IVec2>,
+    rng: StdRng,      // seeded, for AI, spawning and spreading blocks
+    loot_rng: StdRng, // only loot rolls, so nothing else can shift them
+    changed_blocks: Vec<IVec2>, // replaced since the last `take_changed_blocks`
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::new(Perlin::DEFAULT_SEED)
+    }
 }
 
 impl Chunk {
@@ -48,7 +69,13 @@ impl Chunk {
         let block: Block = if y >= 40 {
             let noise =
                 perlin.get([f64::from(x) * scale, f64::from(y) * scale]);
-            if noise < 0.5 {
+            if y >= 70 && noise < -0.6 {
+                // pockets of lava, deep down
+                block_lava(IVec2::new(x, y))
+            } else if y >= 50 && noise < -0.6 {
+                // and toxic sludge closer to the surface
+                block_sludge(IVec2::new(x, y))
+            } else if noise < 0.5 {
                 block_dirt(IVec2::new(x, y))
             } else {
                 block_stone(IVec2::new(x, y))
@@ -90,6 +117,10 @@ impl World {
             chunks: HashMap::new(),
             perlin: Perlin::new(seed),
             active_chunks: Default::default(),
+            rng: StdRng::seed_from_u64(u64::from(seed)),
+            // a different stream than `rng`, from the same seed
+            loot_rng: StdRng::seed_from_u64(u64::from(seed) ^ LOOT_SEED_SALT),
+            changed_blocks: Vec::new(),
         }
     }
 
@@ -146,7 +177,7 @@ impl World {
             for chunk_x in (center_chunk_x - half_chunks_x)
                 ..=(center_chunk_x + half_chunks_x)
             {
-                if self.chunks.get(&IVec2::new(chunk_x, chunk_y)).is_some() {
+                if self.chunks.contains_key(&IVec2::new(chunk_x, chunk_y)) {
                     chunks.push(IVec2::new(chunk_x, chunk_y));
                 }
             }
@@ -163,6 +194,39 @@ impl World {
         chunks
     }
 
+    /// Is `pos` inside one of the active chunks
+    #[must_use]
+    pub fn is_active(&self, pos: Vec2) -> bool {
+        let chunk = IVec2::new(
+            (pos.x.floor() as i32).div_euclid(Chunk::SIZE_I),
+            (pos.y.floor() as i32).div_euclid(Chunk::SIZE_I),
+        );
+        self.active_chunks.contains(&chunk)
+    }
+
+    /// A random tile somewhere in the active chunks
+    pub fn random_active_tile(&mut self) -> Option<IVec2> {
+        if self.active_chunks.is_empty() {
+            return None;
+        }
+        let chunk = self.active_chunks
+            [self.rng.random_range(0..self.active_chunks.len())];
+        let x = self.rng.random_range(0..Chunk::SIZE_I);
+        let y = self.rng.random_range(0..Chunk::SIZE_I);
+        Some(chunk * Chunk::SIZE_I + IVec2::new(x, y))
+    }
+
+    /// The world's seeded rng, for anything that should follow the seed
+    pub fn rng(&mut self) -> &mut StdRng {
+        &mut self.rng
+    }
+
+    /// The seeded rng loot is rolled with. Nothing else draws from it, so
+    /// the same seed and the same things broken give the same drops
+    pub fn loot_rng(&mut self) -> &mut StdRng {
+        &mut self.loot_rng
+    }
+
     #[must_use]
     pub fn get_block(&self, x: i32, y: i32) -> Option<&Block> {
         let chunk_x = x.div_euclid(Chunk::SIZE_I);
@@ -188,7 +252,47 @@ impl World {
             .get_mut(local_x as usize)?
             .get_mut(local_y as usize)
     }
-    pub fn hit_block(&mut self, pos: IVec2, player: &mut Player) {
+    /// Hit the block at `pos` (and any the held tool spreads to)
+    pub fn hit_block(
+        &mut self,
+        pos: IVec2,
+        player: &Player,
+        held: Option<&Item>,
+        stats: &Stats,
+    ) -> Mined {
+        let tool = held.and_then(Item::tool_props).copied();
+        // only spread if the tool is actually working on the hit block
+        let spreads = match (tool, self.get_block(pos.x, pos.y)) {
+            (Some(tool), Some(block)) => tool.can_work(block),
+            _ => false,
+        };
+
+        let mut mined = Mined::default();
+        self.damage_block(pos, player, held, stats, &mut mined);
+
+        if let Some(tool) = tool.filter(|_| spreads) {
+            for offset in tool.spread_offsets(player.look_dir) {
+                let pos = pos + offset;
+                // neighbours have to suit the tool; no bare-hands fallback
+                if self
+                    .get_block(pos.x, pos.y)
+                    .is_some_and(|block| tool.can_work(block))
+                {
+                    self.damage_block(pos, player, held, stats, &mut mined);
+                }
+            }
+        }
+        mined
+    }
+
+    fn damage_block(
+        &mut self,
+        pos: IVec2,
+        player: &Player,
+        held: Option<&Item>,
+        stats: &Stats,
+        mined: &mut Mined,
+    ) {
         let block = self.get_block_mut(pos);
         if block.is_none() {
             return;
@@ -196,20 +300,55 @@ impl World {
         let block = block.unwrap(); // safe because we already handled the None case
 
         if block.can_be_hit() {
-            let mut damage = player.calculate_mining_speed();
-            if player.mining_spread < block.required_level {
-                damage /= 2.0;
-            }
+            // too weak (or the wrong tool) to break it at all
+            let Some(damage) =
+                player.calculate_mining_speed(held, block, stats)
+            else {
+                return;
+            };
             block.health -= damage;
             if block.health <= 0.0 {
-                if let Some(item) = block.drop_item {
-                    player.inventory.add_item(item_from_name(item, 1));
+                mined.xp += block.xp;
+                if let Some(loot) = block.loot {
+                    let multiplier = held
+                        .and_then(Item::tool_props)
+                        .filter(|tool| tool.can_work(block))
+                        .map_or(1.0, |tool| tool.multiplier);
+                    for item in loot.roll(&mut self.loot_rng, multiplier) {
+                        mined.drops.push((pos, item));
+                    }
                 }
                 self.remove_block(pos);
             }
         }
     }
 
+    /// Every loaded block a box at `pos` (top left) of `size` overlaps.
+    /// A box ending exactly on a block edge doesn't touch that block
+    pub fn blocks_overlapping(
+        &self,
+        pos: Vec2,
+        size: Vec2,
+    ) -> impl Iterator<Item = &Block> {
+        let xs = overlapping_tiles(pos.x, pos.x + size.x);
+        overlapping_tiles(pos.y, pos.y + size.y).flat_map(move |y| {
+            xs.clone().filter_map(move |x| self.get_block(x, y))
+        })
+    }
+
+    /// Returns if a box at `pos` (top left) of `size` overlaps any
+    /// collidable block. Unloaded blocks never collide
+    #[must_use]
+    pub fn collides(&self, pos: Vec2, size: Vec2) -> bool {
+        self.blocks_overlapping(pos, size)
+            .any(|block| block.can_collide)
+    }
+
+    /// Every block that was replaced since the last call
+    pub fn take_changed_blocks(&mut self) -> Vec<IVec2> {
+        std::mem::take(&mut self.changed_blocks)
+    }
+
     pub fn heal_block(&mut self, pos: IVec2, heal_amount: f32) {
         let block = self.get_block_mut(pos);
         if let Some(block) = block {
@@ -219,9 +358,137 @@ impl World {
             }
         }
     }
+    /// Put `block` at `pos`, if there is only air there.
+    /// Returns if it was placed
+    pub fn place_block(&mut self, pos: IVec2, block: Block) -> bool {
+        match self.get_block_mut(pos) {
+            Some(old) if old.block_type == BlockName::Air => {
+                *old = block;
+                self.changed_blocks.push(pos);
+                true
+            }
+            _ => false,
+        }
+    }
+
     pub fn remove_block(&mut self, pos: IVec2) {
         if let Some(block) = self.get_block_mut(pos) {
             *block = block_air(pos);
+            self.changed_blocks.push(pos);
+        }
+    }
+}
+
+#[cfg(test)]
+impl World {
+    /// Tiles loaded in test worlds, on each axis
+    pub(crate) const TEST_SIZE: i32 = 128;
+
+    /// Air from (0, 0) to (127, 127) with `blocks` placed in it
+    pub(crate) fn with_blocks(blocks: &[(IVec2, BlockName)]) -> Self {
+        use crate::blocks::block_from_name;
+
+        let mut world = World::new(0);
+        for chunk_y in 0..Self::TEST_SIZE / Chunk::SIZE_I {
+            for chunk_x in 0..Self::TEST_SIZE / Chunk::SIZE_I {
+                let tiles = array::from_fn(|x| {
+                    array::from_fn(|y| {
+                        let (x, y) = Chunk::chunk_to_world(
+                            chunk_x, chunk_y, x as i32, y as i32,
+                        );
+                        block_air(IVec2::new(x, y))
+                    })
+                });
+                let chunk = Chunk {
+                    x: chunk_x,
+                    y: chunk_y,
+                    tiles,
+                };
+                world.chunks.insert(IVec2::new(chunk_x, chunk_y), chunk);
+            }
+        }
+        for &(pos, name) in blocks {
+            assert!(world.place_block(pos, block_from_name(name, pos)));
+        }
+        world.changed_blocks.clear();
+        world
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        item::ItemProps,
+        items::{item_wooden_pickaxe, item_wooden_shovel},
+    };
+
+    const DIRT: IVec2 = IVec2::new(4, 4);
+
+    /// Hit `pos` with `held` until it breaks, returning what dropped
+    fn break_block(world: &mut World, pos: IVec2, held: &Item) -> Vec<Item> {
+        let player = Player::default();
+        let stats = Stats::default();
+        for _ in 0..100 {
+            let mined = world.hit_block(pos, &player, Some(held), &stats);
+            if !mined.drops.is_empty() {
+                return mined.drops.into_iter().map(|(_, item)| item).collect();
+            }
         }
+        panic!("{pos} never broke");
+    }
+
+    fn with_multiplier(mut tool: Item, multiplier: f32) -> Item {
+        if let ItemProps::Tool(props) = &mut tool.props {
+            props.multiplier = multiplier;
+        }
+        tool
+    }
+
+    #[test]
+    fn matching_tool_multiplies_drops() {
+        let shovel = with_multiplier(item_wooden_shovel(1), 3.0);
+        for seed in 0..20 {
+            let mut world = World::with_blocks(&[(DIRT, BlockName::Dirt)]);
+            world.loot_rng = StdRng::seed_from_u64(seed);
+            let drops = break_block(&mut world, DIRT, &shovel);
+            // dirt drops 1 or 2
+            assert!([3, 6].contains(&drops[0].amount), "{drops:?}");
+            assert_eq!(
+                world.get_block(DIRT.x, DIRT.y).map(|b| b.block_type),
+                Some(BlockName::Air)
+            );
+        }
+    }
+
+    #[test]
+    fn other_tools_multiplier_is_ignored() {
+        // a pickaxe doesn't work dirt, so it is mined by hand
+        let pickaxe = with_multiplier(item_wooden_pickaxe(1), 3.0);
+        for seed in 0..20 {
+            let mut world = World::with_blocks(&[(DIRT, BlockName::Dirt)]);
+            world.loot_rng = StdRng::seed_from_u64(seed);
+            let drops = break_block(&mut world, DIRT, &pickaxe);
+            assert!([1, 2].contains(&drops[0].amount), "{drops:?}");
+        }
+    }
+
+    #[test]
+    fn same_seed_same_loot() {
+        let shovel = item_wooden_shovel(1);
+        let drops = |world: &mut World| {
+            (0..8)
+                .flat_map(|x| break_block(world, IVec2::new(x, 4), &shovel))
+                .map(|item| item.amount)
+                .collect::<Vec<_>>()
+        };
+        let blocks: Vec<_> = (0..8)
+            .map(|x| (IVec2::new(x, 4), BlockName::Dirt))
+            .collect();
+        let mut first = World::with_blocks(&blocks);
+        let mut second = World::with_blocks(&blocks);
+        // other users of the world rng don't change the loot
+        second.rng().random::<u64>();
+        assert_eq!(drops(&mut first), drops(&mut second));
     }
 }